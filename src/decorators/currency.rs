@@ -1,28 +1,33 @@
 use super::pluralized_decorator;
 use crate::{Error, ExpectedTypes, Value};
 
+/// Group the digits of an integer string into threes, separated by commas
+fn group_thousands(digits: &str) -> String {
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+/// Format a number as a currency amount, grouped in thousands with two decimals
+/// Negative amounts place the sign before the symbol: -$1,000.00
 fn decorator_currency(input: &Value, symbol: &str) -> Result<String, Error> {
     let n = input.as_float().unwrap();
-    let mut f = format!("{}{:.2}", symbol, n);
-    if !f.contains('.') {
-        f += ".0";
-    }
-    f = f
-        .chars()
-        .rev()
-        .collect::<Vec<char>>()
-        .chunks(3)
-        .map(|c| c.iter().collect::<String>())
-        .collect::<Vec<String>>()
-        .join(",")
-        .replacen(',', "", 1)
-        .chars()
-        .rev()
-        .collect::<String>();
-    if f.chars().nth(1).unwrap() == ',' {
-        f = f.replacen(',', "", 1);
-    }
-    Ok(f)
+    let amount = format!("{:.2}", n.abs());
+    let (whole, fraction) = amount.split_once('.').unwrap_or((&amount, "00"));
+
+    let sign = if n < 0.0 && amount != "0.00" { "-" } else { "" };
+    Ok(format!(
+        "{}{}{}.{}",
+        sign,
+        symbol,
+        group_thousands(whole),
+        fraction
+    ))
 }
 
 define_decorator!(
@@ -82,10 +87,35 @@ define_decorator!(
 
 #[cfg(test)]
 mod test_builtin_functions {
-    use crate::Token;
+    use crate::{OutputFormat, ParserState, Token};
 
     use super::*;
 
+    define_decorator!(
+        name = price,
+        description =
+            "Format values written in dollars as a dollar amount, and anything else as-is",
+        input = ExpectedTypes::IntOrFloat,
+        handler = |decorator, token, input| match token.source_format() {
+            OutputFormat::Dollars => decorator_currency(input, "$"),
+            _ => Ok(input.as_string()),
+        }
+    );
+
+    #[test]
+    fn test_source_format_decorator() {
+        let mut state = ParserState::new();
+        state.decorators.register(price);
+
+        let text = |input: &str, state: &mut ParserState| {
+            Token::new(input, state).unwrap().text().to_string()
+        };
+        assert_eq!("$1,500.00", text("$1500 @price", &mut state));
+        assert_eq!("$7.50", text("$5 + 2.5 @price", &mut state));
+        assert_eq!("1500", text("1500 @price", &mut state));
+        assert_eq!("2.5", text("2.5 @price", &mut state));
+    }
+
     #[test]
     fn test_currencies() {
         assert_eq!(
@@ -116,4 +146,38 @@ mod test_builtin_functions {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn test_currency_rounding() {
+        for (input, expected) in [
+            (Value::Integer(0), "$0.00"),
+            (Value::Integer(1), "$1.00"),
+            (Value::Float(999.5), "$999.50"),
+            (Value::Integer(1000), "$1,000.00"),
+            (Value::Integer(1000000), "$1,000,000.00"),
+            (Value::Float(-1234.567), "-$1,234.57"),
+            (Value::Float(-0.001), "$0.00"),
+        ] {
+            assert_eq!(expected, dollar.call(&Token::dummy(""), &input).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_negative_currencies() {
+        let mut state = ParserState::new();
+        assert_eq!(
+            "-$1,000.00",
+            Token::new("-1000 @usd", &mut state).unwrap().text()
+        );
+        assert_eq!(
+            "-€1,000,000.00",
+            Token::new("-1000000 @euro", &mut state).unwrap().text()
+        );
+        assert_eq!(
+            "-£100.00",
+            pound
+                .call(&Token::dummy(""), &Value::Integer(-100))
+                .unwrap()
+        );
+    }
 }
@@ -0,0 +1,233 @@
+use crate::{DecoratorDefinition, Error, ExpectedTypes, Token, Value};
+use serde::Serialize;
+
+/// Default indentation used by @json_pretty
+const JSON_PRETTY_INDENT: &[u8] = b"  ";
+
+/// Encode a value as JSON, on a single line, or across indented lines
+///
+/// # Arguments
+/// * `token` - Source token, for errors
+/// * `input` - Value to encode
+/// * `indent` - Indentation for each level of nesting, or None for compact output
+fn encode_json(token: &Token, input: &Value, indent: Option<&[u8]>) -> Result<String, Error> {
+    let json = input.to_json();
+    let result = match indent {
+        Some(indent) => {
+            let mut buffer = Vec::new();
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(indent);
+            let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+            json.serialize(&mut serializer)
+                .map(|_| String::from_utf8_lossy(&buffer).to_string())
+        }
+        None => serde_json::to_string(&json),
+    };
+
+    result.map_err(|_| Error::StringFormat {
+        expected_format: "json".to_string(),
+        token: token.clone(),
+    })
+}
+
+pub const JSON: DecoratorDefinition = DecoratorDefinition {
+    name: &["json"],
+    description: "Format a value as JSON",
+    argument: ExpectedTypes::Any,
+    handler: |_decorator, token, input| encode_json(token, input, None),
+};
+
+pub const JSON_PRETTY: DecoratorDefinition = DecoratorDefinition {
+    name: &["json_pretty"],
+    description: "Format a value as indented JSON",
+    argument: ExpectedTypes::Any,
+    handler: |_decorator, token, input| encode_json(token, input, Some(JSON_PRETTY_INDENT)),
+};
+
+/// Quote a CSV field if it contains a comma, quote or line break
+fn csv_field(value: &Value) -> String {
+    let field = value.as_string();
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// Render a value as a comma separated row
+fn csv_row(value: &Value) -> String {
+    value
+        .as_array()
+        .iter()
+        .map(csv_field)
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+pub const CSV: DecoratorDefinition = DecoratorDefinition {
+    name: &["csv"],
+    description: "Format an array as a CSV row, or an array of arrays as CSV rows",
+    argument: ExpectedTypes::Any,
+    handler: |_decorator, _token, input| {
+        if !input.is_compound() {
+            Ok(csv_field(input))
+        } else if input.as_array().iter().any(|e| e.is_compound()) {
+            Ok(input
+                .as_array()
+                .iter()
+                .map(csv_row)
+                .collect::<Vec<String>>()
+                .join("\n"))
+        } else {
+            Ok(csv_row(input))
+        }
+    },
+};
+
+pub const COUNT: DecoratorDefinition = DecoratorDefinition {
+    name: &["count"],
+    description: "Output the number of elements in an array or object, or characters in a string",
+    argument: ExpectedTypes::Any,
+    handler: |_decorator, _token, input| {
+        let count = match input {
+            Value::Array(v) => v.len(),
+            Value::Object(v) => v.len(),
+            Value::String(s) => s.chars().count(),
+            _ => 1,
+        };
+        Ok(count.to_string())
+    },
+};
+
+#[cfg(test)]
+mod test_builtin_functions {
+    use crate::value::ObjectType;
+
+    use super::*;
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            "[1,2,3]",
+            JSON.call(
+                &Token::dummy(""),
+                &Value::Array(vec![
+                    Value::Integer(1),
+                    Value::Integer(2),
+                    Value::Integer(3)
+                ])
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "{\"a\":{\"b\":[1,\"c\"]}}",
+            JSON.call(
+                &Token::dummy(""),
+                &Value::Object(ObjectType::from([(
+                    Value::from("a"),
+                    Value::Object(ObjectType::from([(
+                        Value::from("b"),
+                        Value::Array(vec![Value::Integer(1), Value::from("c")])
+                    )]))
+                )]))
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "\"te\\\"st\"",
+            JSON.call(&Token::dummy(""), &Value::from("te\"st"))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_json_pretty() {
+        let input = Value::Object(ObjectType::from([(
+            Value::from("a"),
+            Value::Object(ObjectType::from([(
+                Value::from("b"),
+                Value::Array(vec![Value::Integer(1)]),
+            )])),
+        )]));
+
+        assert_eq!(
+            "{\n  \"a\": {\n    \"b\": [\n      1\n    ]\n  }\n}",
+            JSON_PRETTY.call(&Token::dummy(""), &input).unwrap()
+        );
+        assert_eq!(
+            "{\n\t\"a\": {\n\t\t\"b\": [\n\t\t\t1\n\t\t]\n\t}\n}",
+            encode_json(&Token::dummy(""), &input, Some(&b"\t"[..])).unwrap()
+        );
+
+        // Through the parser, as a registered decorator
+        assert_token_text!(
+            "{'a': {'b': [1]}} @json_pretty",
+            "{\n  \"a\": {\n    \"b\": [\n      1\n    ]\n  }\n}"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            "1,2,3",
+            CSV.call(
+                &Token::dummy(""),
+                &Value::Array(vec![
+                    Value::Integer(1),
+                    Value::Integer(2),
+                    Value::Integer(3)
+                ])
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "1,2\n3,4",
+            CSV.call(
+                &Token::dummy(""),
+                &Value::Array(vec![
+                    Value::Array(vec![Value::Integer(1), Value::Integer(2)]),
+                    Value::Array(vec![Value::Integer(3), Value::Integer(4)])
+                ])
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "\"a,b\",\"say \"\"hi\"\"\"",
+            CSV.call(
+                &Token::dummy(""),
+                &Value::Array(vec![Value::from("a,b"), Value::from("say \"hi\"")])
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "5",
+            CSV.call(&Token::dummy(""), &Value::Integer(5)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_count() {
+        assert_eq!(
+            "3",
+            COUNT
+                .call(
+                    &Token::dummy(""),
+                    &Value::Array(vec![
+                        Value::Integer(1),
+                        Value::Integer(2),
+                        Value::Integer(3)
+                    ])
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            "5",
+            COUNT
+                .call(&Token::dummy(""), &Value::from("héllo"))
+                .unwrap()
+        );
+        assert_eq!(
+            "1",
+            COUNT.call(&Token::dummy(""), &Value::Integer(42)).unwrap()
+        );
+    }
+}
@@ -1,5 +1,6 @@
-use crate::{value::ObjectType, Error, ExpectedTypes, Token, Value};
+use crate::{DisplayConfig, Error, ExpectedTypes, Token, Value};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 #[macro_use]
 pub mod decorator_macros {
@@ -44,6 +45,7 @@ pub mod decorator_macros {
 }
 
 mod currency;
+mod data;
 mod numeric;
 mod primitives;
 mod string;
@@ -51,35 +53,56 @@ mod string;
 /// Handler for executing a decorator
 pub type DecoratorHandler = fn(&DecoratorDefinition, &Token, &Value) -> Result<String, Error>;
 
-/// Holds a set of callable decorators
+/// Handler for executing a decorator that follows the state's display options
+pub type ConfigDecoratorHandler =
+    fn(&DecoratorDefinition, &Token, &Value, &DisplayConfig) -> Result<String, Error>;
+
+/// Holds a set of callable decorators, and the config-aware handlers of any that have one
+/// Clones share the same underlying maps until one of them is modified
 #[derive(Clone)]
-pub struct DecoratorTable(HashMap<String, DecoratorDefinition>);
+pub struct DecoratorTable(
+    Arc<HashMap<String, DecoratorDefinition>>,
+    Arc<HashMap<String, ConfigDecoratorHandler>>,
+);
 impl DecoratorTable {
     /// Initialize a new decorator table, complete with default builtin decorators
     pub fn new() -> DecoratorTable {
-        let mut table: DecoratorTable = DecoratorTable(HashMap::new());
+        let mut table: DecoratorTable =
+            DecoratorTable(Arc::new(HashMap::new()), Arc::new(HashMap::new()));
 
         table.register(numeric::hex);
         table.register(numeric::oct);
         table.register(numeric::bin);
         table.register(numeric::sci);
+        table.register(numeric::sci3);
         table.register(numeric::utc);
+        table.register(numeric::local);
+        table.register(numeric::duration_ms);
+        table.register(numeric::bytes);
+        table.register(numeric::bytes_si);
 
         table.register(currency::dollar);
         table.register(currency::euro);
         table.register(currency::pound);
         table.register(currency::yen);
 
-        table.register(primitives::DEFAULT);
-        table.register(primitives::FLOAT);
-        table.register(primitives::INT);
-        table.register(primitives::BOOL);
-        table.register(primitives::ARRAY);
-        table.register(primitives::OBJECT);
+        table.register_with_config(primitives::DEFAULT, primitives::default);
+        table.register_with_config(primitives::FLOAT, primitives::float);
+        table.register_with_config(primitives::INT, primitives::int);
+        table.register_with_config(primitives::BOOL, primitives::bool);
+        table.register_with_config(primitives::ARRAY, primitives::array);
+        table.register_with_config(primitives::OBJECT, primitives::object);
 
         table.register(string::ROMAN);
         table.register(string::ORDINAL);
         table.register(string::PERCENTAGE);
+        table.register(string::ROT13);
+        table.register(string::REVERSE);
+
+        table.register(data::JSON);
+        table.register(data::JSON_PRETTY);
+        table.register(data::CSV);
+        table.register(data::COUNT);
 
         table
     }
@@ -90,8 +113,37 @@ impl DecoratorTable {
     /// * `name` - Decorator name
     /// * `handler` - Decorator handler
     pub fn register(&mut self, definition: DecoratorDefinition) {
+        let table = Arc::make_mut(&mut self.0);
+        for name in definition.name() {
+            table.insert(name.to_string(), definition.clone());
+        }
+
+        // A config-aware handler registered under the same name no longer applies
+        for name in definition.name() {
+            if self.1.contains_key(*name) {
+                Arc::make_mut(&mut self.1).remove(*name);
+            }
+        }
+    }
+
+    /// Register a decorator in the table, along with a handler that is used instead of
+    /// the definition's own when the decorator is called with display options
+    ///
+    /// # Arguments
+    /// * `definition` - Decorator definition
+    /// * `handler` - Config-aware decorator handler
+    pub fn register_with_config(
+        &mut self,
+        definition: DecoratorDefinition,
+        handler: ConfigDecoratorHandler,
+    ) {
+        let handlers = Arc::make_mut(&mut self.1);
+        for name in definition.name() {
+            handlers.insert(name.to_string(), handler);
+        }
+        let table = Arc::make_mut(&mut self.0);
         for name in definition.name() {
-            self.0.insert(name.to_string(), definition.clone());
+            table.insert(name.to_string(), definition.clone());
         }
     }
 
@@ -132,6 +184,28 @@ impl DecoratorTable {
             }),
         }
     }
+
+    /// Call a decorator, using its config-aware handler if it has one
+    ///
+    /// # Arguments
+    /// * `name` - Decorator name
+    /// * `args` - Decorator arguments
+    /// * `config` - Display options for the output
+    pub fn call_with_config(
+        &self,
+        name: &str,
+        token: &Token,
+        arg: &Value,
+        config: &DisplayConfig,
+    ) -> Result<String, Error> {
+        match (self.0.get(name), self.1.get(name)) {
+            (Some(f), Some(handler)) => match f.validate(token, arg) {
+                Some(error) => Err(error),
+                None => handler(f, token, arg, config),
+            },
+            _ => self.call(name, token, arg),
+        }
+    }
 }
 
 impl Default for DecoratorTable {
@@ -221,30 +295,29 @@ pub fn pluralized_decorator(
     input: &Value,
 ) -> Result<String, Error> {
     match input {
-        Value::Array(v) => {
-            let mut output: Vec<Value> = Vec::new();
-            for value in v {
-                match decorator.call(token, value) {
-                    Ok(s) => output.push(Value::from(s)),
-                    Err(e) => return Err(e),
-                }
-            }
-            Ok(Value::from(output).as_string())
-        }
-
-        Value::Object(v) => {
-            let mut output: ObjectType = ObjectType::new();
-            for (value, key) in v {
-                match decorator.call(token, value) {
-                    Ok(s) => {
-                        output.insert(key.clone(), Value::from(s));
-                    }
-                    Err(e) => return Err(e),
-                }
-            }
-            Ok(Value::from(output).as_string())
-        }
-
+        Value::Array(_) | Value::Object(_) => Ok(input
+            .map_values(|value| decorator.call(token, value).map(Value::from))?
+            .as_string()),
         _ => decorator.call(token, input),
     }
 }
+
+/// Runs a config-aware decorator handler on plural types
+pub fn pluralized_config_decorator(
+    decorator: &DecoratorDefinition,
+    handler: ConfigDecoratorHandler,
+    token: &Token,
+    input: &Value,
+    config: &DisplayConfig,
+) -> Result<String, Error> {
+    let call = |value: &Value| match decorator.validate(token, value) {
+        Some(error) => Err(error),
+        None => handler(decorator, token, value, config),
+    };
+    match input {
+        Value::Array(_) | Value::Object(_) => Ok(input
+            .map_values(|value| call(value).map(Value::from))?
+            .as_string()),
+        _ => call(input),
+    }
+}
@@ -1,6 +1,6 @@
 use super::pluralized_decorator;
 use crate::{Error, ExpectedTypes};
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
 
 define_decorator!(
     name = hex,
@@ -54,6 +54,19 @@ define_decorator!(
     }
 );
 
+define_decorator!(
+    name = sci3,
+    description = "Scientific number formatting, with the mantissa rounded to 3 decimal places, such as 1.235e4",
+    input = ExpectedTypes::IntOrFloat,
+    handler = |decorator, token, input| {
+        if decorator.arg().strict_matches(input) {
+            Ok(format!("{:.3e}", input.as_float().unwrap()))
+        } else {
+            pluralized_decorator(decorator, token, input)
+        }
+    }
+);
+
 define_decorator!(
     name = utc,
     description = "Interprets an integer as a timestamp, and formats it in UTC standard",
@@ -77,6 +90,132 @@ define_decorator!(
     }
 );
 
+define_decorator!(
+    name = local,
+    description = "Interprets an integer as a timestamp, and formats it in the local timezone",
+    input = ExpectedTypes::IntOrFloat,
+    handler = |decorator, token, input| {
+        if decorator.arg().strict_matches(input) {
+            let n = input.as_int().unwrap();
+            match Local.timestamp_opt(n, 0).single() {
+                Some(datetime) => Ok(datetime.format("%Y-%m-%d %H:%M:%S").to_string()),
+                None => Err(Error::Range {
+                    value: input.clone(),
+                    token: token.clone(),
+                }),
+            }
+        } else {
+            pluralized_decorator(decorator, token, input)
+        }
+    }
+);
+
+define_decorator!(
+    name = duration_ms,
+    description = "Interprets an integer as a number of milliseconds, and formats it as a duration, such as 1m 2.345s",
+    input = ExpectedTypes::IntOrFloat,
+    handler = |decorator, token, input| {
+        if decorator.arg().strict_matches(input) {
+            let ms = input.as_int().unwrap();
+            if ms < 0 {
+                return Err(Error::Range {
+                    value: input.clone(),
+                    token: token.clone(),
+                });
+            }
+
+            let (hours, minutes) = (ms / 3_600_000, ms / 60_000 % 60);
+            let seconds = format!("{}.{:03}s", ms / 1000 % 60, ms % 1000);
+            Ok(if hours > 0 {
+                format!("{}h {}m {}", hours, minutes, seconds)
+            } else if minutes > 0 {
+                format!("{}m {}", minutes, seconds)
+            } else {
+                seconds
+            })
+        } else {
+            pluralized_decorator(decorator, token, input)
+        }
+    }
+);
+
+/// Format a number of bytes using the largest unit it reaches, to at most one decimal place
+///
+/// # Arguments
+/// * `n_bytes` - Number of bytes
+/// * `base` - Size of each unit relative to the last, 1024 or 1000
+/// * `units` - Unit names, starting with bytes
+fn format_bytes(n_bytes: f64, base: f64, units: &[&str]) -> String {
+    let mut size = n_bytes;
+    let mut unit = 0;
+    while size >= base && unit < units.len() - 1 {
+        size /= base;
+        unit += 1;
+    }
+
+    // Rounding can carry a value into the next unit, such as 1023.99 KiB
+    let mut size = (size * 10.0).round() / 10.0;
+    if size >= base && unit < units.len() - 1 {
+        size /= base;
+        unit += 1;
+    }
+
+    let size = format!("{:.1}", size);
+    format!("{} {}", size.trim_end_matches(".0"), units[unit])
+}
+
+define_decorator!(
+    name = bytes,
+    description =
+        "Interprets a number as a size in bytes, and formats it in binary units, such as 1.5 KiB",
+    input = ExpectedTypes::IntOrFloat,
+    handler = |decorator, token, input| {
+        if decorator.arg().strict_matches(input) {
+            let n = input.as_float().unwrap();
+            if n < 0.0 || !n.is_finite() {
+                return Err(Error::Range {
+                    value: input.clone(),
+                    token: token.clone(),
+                });
+            }
+
+            Ok(format_bytes(
+                n,
+                1024.0,
+                &["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"],
+            ))
+        } else {
+            pluralized_decorator(decorator, token, input)
+        }
+    }
+);
+
+define_decorator!(
+    name = bytes_si,
+    description =
+        "Interprets a number as a size in bytes, and formats it in decimal units, such as 1.5 kB",
+    input = ExpectedTypes::IntOrFloat,
+    handler = |decorator, token, input| {
+        if decorator.arg().strict_matches(input) {
+            let n = input.as_float().unwrap();
+            if n < 0.0 || !n.is_finite() {
+                return Err(Error::Range {
+                    value: input.clone(),
+                    token: token.clone(),
+                });
+            }
+
+            Ok(format_bytes(
+                n,
+                1000.0,
+                &["B", "kB", "MB", "GB", "TB", "PB", "EB"],
+            ))
+        } else {
+            pluralized_decorator(decorator, token, input)
+        }
+    }
+);
+
 #[cfg(test)]
 mod test_builtin_functions {
     use super::*;
@@ -133,4 +272,101 @@ mod test_builtin_functions {
             sci.call(&Token::dummy(""), &Value::Float(0.081)).unwrap()
         );
     }
+
+    #[test]
+    fn test_sci3() {
+        assert_eq!(
+            "1.235e4",
+            sci3.call(&Token::dummy(""), &Value::Integer(12346))
+                .unwrap()
+        );
+        assert_eq!(
+            "-1.235e-4",
+            sci3.call(&Token::dummy(""), &Value::Float(-0.000123456))
+                .unwrap()
+        );
+        assert_eq!(
+            "8.000e0",
+            sci3.call(&Token::dummy(""), &Value::Integer(8)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_duration_ms() {
+        assert_eq!(
+            "1.234s",
+            duration_ms
+                .call(&Token::dummy(""), &Value::Integer(1234))
+                .unwrap()
+        );
+        assert_eq!(
+            "1m 1.500s",
+            duration_ms
+                .call(&Token::dummy(""), &Value::Integer(61500))
+                .unwrap()
+        );
+        assert_eq!(
+            "1h 0m 0.001s",
+            duration_ms
+                .call(&Token::dummy(""), &Value::Integer(3_600_001))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_local() {
+        let format = "%Y-%m-%d %H:%M:%S";
+        let output = local
+            .call(&Token::dummy(""), &Value::Integer(1700000000))
+            .unwrap();
+        let parsed = NaiveDateTime::parse_from_str(&output, format).unwrap();
+
+        // The local time is within a day of the UTC time, whatever the timezone
+        let utc_output = utc
+            .call(&Token::dummy(""), &Value::Integer(1700000000))
+            .unwrap();
+        let utc_parsed = NaiveDateTime::parse_from_str(&utc_output, format).unwrap();
+        assert!((parsed - utc_parsed).num_hours().abs() <= 14);
+
+        assert!(matches!(
+            local.call(&Token::dummy(""), &Value::Integer(i64::MAX)),
+            Err(Error::Range { .. })
+        ));
+    }
+
+    #[test]
+    fn test_bytes() {
+        for (input, expected) in [
+            (0, "0 B"),
+            (1023, "1023 B"),
+            (1536, "1.5 KiB"),
+            (1048575, "1 MiB"),
+            (1073741824, "1 GiB"),
+            (5 * 1099511627776, "5 TiB"),
+        ] {
+            assert_eq!(
+                expected,
+                bytes
+                    .call(&Token::dummy(""), &Value::Integer(input))
+                    .unwrap()
+            );
+        }
+
+        assert_eq!(
+            "1.5 kB",
+            bytes_si
+                .call(&Token::dummy(""), &Value::Integer(1500))
+                .unwrap()
+        );
+        assert_eq!(
+            "2.3 GB",
+            bytes_si
+                .call(&Token::dummy(""), &Value::Integer(2_345_678_901))
+                .unwrap()
+        );
+        assert!(matches!(
+            bytes.call(&Token::dummy(""), &Value::Integer(-1)),
+            Err(Error::Range { .. })
+        ));
+    }
 }
@@ -1,70 +1,121 @@
-use crate::{DecoratorDefinition, ExpectedTypes, Value};
+use crate::{DecoratorDefinition, DisplayConfig, Error, ExpectedTypes, Token, Value};
 
-use super::pluralized_decorator;
+use super::pluralized_config_decorator;
 
 pub const DEFAULT: DecoratorDefinition = DecoratorDefinition {
     name: &["default"],
     description: "Default formatter, type dependent",
     argument: ExpectedTypes::Any,
-    handler: |_, token, input| match input {
-        Value::Boolean(_) => (BOOL.handler)(&BOOL, token, input),
-        Value::Integer(_) => (INT.handler)(&INT, token, input),
-        Value::Float(_) => (FLOAT.handler)(&FLOAT, token, input),
-        Value::Array(_) => (ARRAY.handler)(&ARRAY, token, input),
-        Value::Object(_) => (OBJECT.handler)(&OBJECT, token, input),
-        Value::String(s) => Ok(s.to_string()),
-        Value::Identifier(_) => Ok("".to_string()),
-        Value::None => Ok("".to_string()),
-    },
+    handler: |decorator, token, input| default(decorator, token, input, &DisplayConfig::default()),
 };
 
 pub const FLOAT: DecoratorDefinition = DecoratorDefinition {
     name: &["float"],
     description: "Format a number as floating point",
     argument: ExpectedTypes::IntOrFloat,
-    handler: |decorator, token, input| {
-        if decorator.arg().strict_matches(input) {
-            Ok(Value::Float(input.as_float().unwrap()).as_string())
-        } else {
-            pluralized_decorator(decorator, token, input)
-        }
-    },
+    handler: |decorator, token, input| float(decorator, token, input, &DisplayConfig::default()),
 };
 
 pub const INT: DecoratorDefinition = DecoratorDefinition {
     name: &["int", "integer"],
     description: "Format a number as an integer",
     argument: ExpectedTypes::IntOrFloat,
-    handler: |decorator, token, input| {
-        if decorator.arg().strict_matches(input) {
-            Ok(Value::Integer(input.as_int().unwrap()).as_string())
-        } else {
-            pluralized_decorator(decorator, token, input)
-        }
-    },
+    handler: |decorator, token, input| int(decorator, token, input, &DisplayConfig::default()),
 };
 
 pub const BOOL: DecoratorDefinition = DecoratorDefinition {
     name: &["bool", "boolean"],
     description: "Format a number as a boolean",
     argument: ExpectedTypes::Any,
-    handler: |_, _, input| Ok(Value::Boolean(input.as_bool()).as_string()),
+    handler: |decorator, token, input| bool(decorator, token, input, &DisplayConfig::default()),
 };
 
 pub const ARRAY: DecoratorDefinition = DecoratorDefinition {
     name: &["array"],
     description: "Format a number as an array",
     argument: ExpectedTypes::Any,
-    handler: |_, _, input| Ok(Value::Array(input.as_array()).as_string()),
+    handler: |decorator, token, input| array(decorator, token, input, &DisplayConfig::default()),
 };
 
 pub const OBJECT: DecoratorDefinition = DecoratorDefinition {
     name: &["object"],
     description: "Format a number as an object",
     argument: ExpectedTypes::Any,
-    handler: |_, _, input| Ok(Value::Object(input.as_object()).as_string()),
+    handler: |decorator, token, input| object(decorator, token, input, &DisplayConfig::default()),
 };
 
+// Config-aware handlers for the primitive decorators, which follow the state's display options
+
+pub fn default(
+    _decorator: &DecoratorDefinition,
+    token: &Token,
+    input: &Value,
+    config: &DisplayConfig,
+) -> Result<String, Error> {
+    match input {
+        Value::Boolean(_) => bool(&BOOL, token, input, config),
+        Value::Integer(_) => int(&INT, token, input, config),
+        Value::Float(_) => float(&FLOAT, token, input, config),
+        Value::Array(_) => array(&ARRAY, token, input, config),
+        Value::Object(_) => object(&OBJECT, token, input, config),
+        Value::Identifier(_) => Ok("".to_string()),
+        _ => Ok(input.display(config)),
+    }
+}
+
+pub fn float(
+    decorator: &DecoratorDefinition,
+    token: &Token,
+    input: &Value,
+    config: &DisplayConfig,
+) -> Result<String, Error> {
+    if decorator.arg().strict_matches(input) {
+        Ok(Value::Float(input.as_float().unwrap()).display(config))
+    } else {
+        pluralized_config_decorator(decorator, float, token, input, config)
+    }
+}
+
+pub fn int(
+    decorator: &DecoratorDefinition,
+    token: &Token,
+    input: &Value,
+    config: &DisplayConfig,
+) -> Result<String, Error> {
+    if decorator.arg().strict_matches(input) {
+        Ok(Value::Integer(input.as_int().unwrap()).display(config))
+    } else {
+        pluralized_config_decorator(decorator, int, token, input, config)
+    }
+}
+
+pub fn bool(
+    _decorator: &DecoratorDefinition,
+    _token: &Token,
+    input: &Value,
+    config: &DisplayConfig,
+) -> Result<String, Error> {
+    Ok(Value::Boolean(input.as_bool()).display(config))
+}
+
+pub fn array(
+    _decorator: &DecoratorDefinition,
+    _token: &Token,
+    input: &Value,
+    config: &DisplayConfig,
+) -> Result<String, Error> {
+    Ok(Value::Array(input.as_array()).display(config))
+}
+
+pub fn object(
+    _decorator: &DecoratorDefinition,
+    _token: &Token,
+    input: &Value,
+    config: &DisplayConfig,
+) -> Result<String, Error> {
+    Ok(Value::Object(input.as_object()).display(config))
+}
+
 #[cfg(test)]
 mod test_builtin_functions {
     use crate::Token;
@@ -82,7 +133,7 @@ mod test_builtin_functions {
             FLOAT.call(&Token::dummy(""), &Value::Float(81.0)).unwrap()
         );
         assert_eq!(
-            "0.0",
+            "1e-10",
             FLOAT
                 .call(&Token::dummy(""), &Value::Float(0.0000000001))
                 .unwrap()
@@ -2,13 +2,18 @@ use crate::{DecoratorDefinition, Error, ExpectedTypes, Value};
 
 use super::pluralized_decorator;
 
+/// Number of decimal places kept by @percentage
+const PERCENTAGE_PRECISION: i32 = 2;
+
 pub const PERCENTAGE: DecoratorDefinition = DecoratorDefinition {
     name: &["percentage", "percent"],
     description: "Format a floating point number as a percentage",
     argument: ExpectedTypes::IntOrFloat,
     handler: |decorator, token, input| {
         if decorator.arg().strict_matches(input) {
-            Ok(format!("{}%", input.as_float().unwrap() * 100.0))
+            let multiplier = f64::powi(10.0, PERCENTAGE_PRECISION);
+            let percent = (input.as_float().unwrap() * 100.0 * multiplier).round() / multiplier;
+            Ok(format!("{}%", percent))
         } else {
             pluralized_decorator(decorator, token, input)
         }
@@ -78,9 +83,31 @@ pub const ROMAN: DecoratorDefinition = DecoratorDefinition {
     },
 };
 
+pub const ROT13: DecoratorDefinition = DecoratorDefinition {
+    name: &["rot13"],
+    description: "Rotate the letters of a string by 13 places",
+    argument: ExpectedTypes::String,
+    handler: |_decorator, _token, input| Ok(crate::functions::str::rot13(&input.as_string())),
+};
+
+pub const REVERSE: DecoratorDefinition = DecoratorDefinition {
+    name: &["reverse"],
+    description: "Reverse the elements of an array, or the characters of a string",
+    argument: ExpectedTypes::Any,
+    handler: |decorator, token, input| match input {
+        Value::Array(v) => Ok(Value::Array(v.iter().rev().cloned().collect()).as_string()),
+        Value::String(s) => Ok(s.chars().rev().collect()),
+        _ => Err(Error::DecoratorArgumentType {
+            name: decorator.signature(),
+            expected_type: ExpectedTypes::String,
+            token: token.clone(),
+        }),
+    },
+};
+
 #[cfg(test)]
 mod test_builtin_functions {
-    use crate::Token;
+    use crate::{value::ObjectType, Token};
 
     use super::*;
 
@@ -102,6 +129,60 @@ mod test_builtin_functions {
                 .call(&Token::dummy(""), &Value::Float(0.325))
                 .unwrap()
         );
+        assert_eq!(
+            "33.33%",
+            PERCENTAGE
+                .call(&Token::dummy(""), &Value::Float(0.3333))
+                .unwrap()
+        );
+        assert_eq!(
+            "50%",
+            PERCENTAGE
+                .call(&Token::dummy(""), &Value::Float(0.5))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rot13() {
+        assert_eq!(
+            "Uryyb",
+            ROT13
+                .call(&Token::dummy(""), &Value::from("Hello"))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_reverse() {
+        assert_eq!(
+            "olleh",
+            REVERSE
+                .call(&Token::dummy(""), &Value::from("hello"))
+                .unwrap()
+        );
+        assert_eq!(
+            "[3, 2, 1]",
+            REVERSE
+                .call(
+                    &Token::dummy(""),
+                    &Value::Array(vec![
+                        Value::Integer(1),
+                        Value::Integer(2),
+                        Value::Integer(3)
+                    ])
+                )
+                .unwrap()
+        );
+        for input in [
+            Value::Integer(123),
+            Value::Object(ObjectType::from([(Value::from("a"), Value::Integer(1))])),
+        ] {
+            assert!(matches!(
+                REVERSE.call(&Token::dummy(""), &input),
+                Err(Error::DecoratorArgumentType { .. })
+            ));
+        }
     }
 
     #[test]
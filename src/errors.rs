@@ -29,6 +29,10 @@ pub enum Error {
         token: Token
     },
 
+    /// An error caused by attempting an assignment while the parser is read-only
+    #[error("assignments are not allowed in read-only mode at {0}")]
+    ReadOnly(Token),
+
     /// An error caused by a calculation that resulted in an overflow
     #[error("arithmetic overflow at {0}")]
     Overflow(Token),
@@ -37,6 +41,10 @@ pub enum Error {
     #[error("arithmetic underflow at {0}")]
     Underflow(Token),
 
+    /// An error caused by dividing by zero, or taking a modulus by zero
+    #[error("division or modulo by zero at {0}")]
+    DivideByZero(Token),
+
     /// An error caused by attempting to parse an value
     #[error("{input} could not be parsed as {expected_type} at {token}")]
     ValueParsing {
@@ -83,6 +91,16 @@ pub enum Error {
         token: Token,
     },
 
+    /// An error caused by a value not matching the structure it was validated against
+    #[error("validation failed: {reason} at {token}")]
+    Validation {
+        /// Description of the mismatches found
+        reason: String,
+        
+        /// token at which the error occured
+        token: Token
+    },
+
     /// An error caused by attempting to use an unassigned variable
     #[error("undefined variable {name} at {token}")]
     VariableName {
@@ -135,6 +153,26 @@ pub enum Error {
     #[error("stack overflow at {0}")]
     StackOverflow(Token),
 
+    /// An error caused by an evaluation making more function calls than allowed
+    #[error("exceeded the budget of {limit} function calls at {token}")]
+    Budget {
+        /// Maximum number of function calls allowed
+        limit: usize,
+        
+        /// token at which the error occured
+        token: Token
+    },
+
+    /// An error caused by an evaluation taking more steps than allowed
+    #[error("evaluation exceeded the limit of {limit} steps at {token}")]
+    Timeout {
+        /// Maximum number of evaluation steps allowed
+        limit: usize,
+        
+        /// token at which the error occured
+        token: Token
+    },
+
     /// An error caused by attempting to use a function with ambiguous arguments
     #[error("function parameters for {signature} are ambiguous at {token}")]
     AmbiguousFunctionDefinition {
@@ -74,7 +74,7 @@ impl Extension {
     /// * `name` - Function name
     /// * `args` - Values to pass in
     pub fn call_function(
-        &mut self,
+        &self,
         name: &str,
         args: &[Value],
         variables: &mut HashMap<String, Value>,
@@ -100,7 +100,7 @@ impl Extension {
     /// * `name` - Decorator name
     /// * `arg` - Value to pass in
     pub fn call_decorator(
-        &mut self,
+        &self,
         name: &str,
         token: &Token,
         variables: &mut HashMap<String, Value>,
@@ -196,7 +196,7 @@ mod test_extensions {
 
     #[test]
     fn test_call_simple() {
-        let mut e = Extension::new("example_extensions/simple_extension.js").unwrap();
+        let e = Extension::new("example_extensions/simple_extension.js").unwrap();
         assert_eq!(
             Value::Float(3.0),
             e.call_function(
@@ -210,7 +210,7 @@ mod test_extensions {
 
     #[test]
     fn test_call_function() {
-        let mut e = Extension::new("example_extensions/simple_extension.js").unwrap();
+        let e = Extension::new("example_extensions/simple_extension.js").unwrap();
         assert_eq!(
             Value::Integer(3),
             e.call_function(
@@ -224,7 +224,7 @@ mod test_extensions {
 
     #[test]
     fn test_maintains_state() {
-        let mut e = Extension::new("example_extensions/stateful_functions.js").unwrap();
+        let e = Extension::new("example_extensions/stateful_functions.js").unwrap();
         let mut state: HashMap<String, Value> = HashMap::new();
         state.insert("foo".to_string(), Value::String("bar".to_string()));
         assert_eq!(
@@ -241,7 +241,7 @@ mod test_extensions {
 
     #[test]
     fn test_can_fail() {
-        let mut e = Extension::new("example_extensions/simple_extension.js").unwrap();
+        let e = Extension::new("example_extensions/simple_extension.js").unwrap();
         assert_eq!(
             true,
             matches!(e.call_function("add", &[], &mut HashMap::new()), Err(_))
@@ -257,7 +257,7 @@ mod test_extensions {
 
     #[test]
     fn test_call_decorator() {
-        let mut e = Extension::new("example_extensions/simple_extension.js").unwrap();
+        let e = Extension::new("example_extensions/simple_extension.js").unwrap();
         let mut state: HashMap<String, Value> = HashMap::new();
         let mut token = Token::dummy("");
         token.set_value(Value::Integer(0xFF));
@@ -276,7 +276,7 @@ mod test_extensions {
     */
     #[test]
     fn test_color() {
-        let mut e = Extension::new("example_extensions/simple_extension.js").unwrap();
+        let e = Extension::new("example_extensions/simple_extension.js").unwrap();
         assert_eq!(
             Value::Integer(3),
             e.call_function(
@@ -2,17 +2,20 @@ use crate::{Error, Token, Value};
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::extensions::extension::Extension;
 use crate::extensions::runtime::ExtensionsRuntime;
 
 /// Holds a set of registered extensions
+/// Clones share the same underlying map until one of them is modified, which keeps
+/// the per-call state clones made by user functions cheap
 #[derive(Deserialize, Serialize, Clone)]
-pub struct ExtensionTable(HashMap<String, Extension>);
+pub struct ExtensionTable(Arc<HashMap<String, Extension>>);
 impl ExtensionTable {
     /// Create a new empty table
     pub fn new() -> Self {
-        Self(HashMap::new())
+        Self(Arc::new(HashMap::new()))
     }
 
     /// Add an extension
@@ -21,7 +24,7 @@ impl ExtensionTable {
     /// * `filename` - File name
     /// * `extension` - Extension to add
     pub fn add(&mut self, filename: &str, extension: Extension) {
-        self.0.insert(filename.to_string(), extension);
+        Arc::make_mut(&mut self.0).insert(filename.to_string(), extension);
     }
 
     /// Load an extension from a filename
@@ -30,29 +33,29 @@ impl ExtensionTable {
     /// * `filename` - File name
     pub fn load(&mut self, filename: &str) -> Result<Extension, rustyscript::Error> {
         let e = ExtensionsRuntime::load_extension(filename)?;
-        self.0.insert(filename.to_string(), e.clone());
+        Arc::make_mut(&mut self.0).insert(filename.to_string(), e.clone());
         Ok(e)
     }
 
     /// Attempt to load all extensions in a directory
     pub fn load_all(&mut self, path: &str) -> Vec<Result<Extension, rustyscript::Error>> {
         let e = ExtensionsRuntime::load_extensions(path);
-        self.0.clear();
+        let table = Arc::make_mut(&mut self.0);
+        table.clear();
         for extension in e.iter().flatten() {
-            self.0
-                .insert(extension.filename().to_string(), extension.clone());
+            table.insert(extension.filename().to_string(), extension.clone());
         }
         e
     }
 
     /// Delete an extension
     pub fn remove(&mut self, filename: &str) {
-        self.0.remove(filename);
+        Arc::make_mut(&mut self.0).remove(filename);
     }
 
     /// Returns the full list of extensions available
-    pub fn all(&mut self) -> Vec<&mut Extension> {
-        let mut a = Vec::from_iter(self.0.values_mut());
+    pub fn all(&self) -> Vec<&Extension> {
+        let mut a = Vec::from_iter(self.0.values());
         a.sort_by(|f1, f2| f1.name().cmp(f2.name()));
         a
     }
@@ -61,7 +64,7 @@ impl ExtensionTable {
     ///
     /// # Arguments
     /// * `name` - Function name
-    pub fn has_function(&mut self, name: &str) -> bool {
+    pub fn has_function(&self, name: &str) -> bool {
         for extension in self.all() {
             if extension.has_function(name) {
                 return true;
@@ -72,7 +75,7 @@ impl ExtensionTable {
 
     /// Try to call a function in the loaded extensions
     pub fn call_function(
-        &mut self,
+        &self,
         name: &str,
         token: &Token,
         args: &[Value],
@@ -96,7 +99,7 @@ impl ExtensionTable {
     ///
     /// # Arguments
     /// * `name` - Decorator name
-    pub fn has_decorator(&mut self, name: &str) -> bool {
+    pub fn has_decorator(&self, name: &str) -> bool {
         for extension in self.all() {
             if extension.has_decorator(name) {
                 return true;
@@ -107,7 +110,7 @@ impl ExtensionTable {
 
     /// Try to call a decorator in the loaded extensions
     pub fn call_decorator(
-        &mut self,
+        &self,
         name: &str,
         token: &Token,
         variables: &mut HashMap<String, Value>,
@@ -131,3 +134,23 @@ impl Default for ExtensionTable {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test_extension_table {
+    use super::*;
+
+    #[test]
+    fn test_clone_is_shared() {
+        let mut table = ExtensionTable::new();
+        table
+            .load("example_extensions/simple_extension.js")
+            .unwrap();
+        let mut inner = table.clone();
+        assert!(Arc::ptr_eq(&table.0, &inner.0));
+
+        inner.remove("example_extensions/simple_extension.js");
+        assert!(!Arc::ptr_eq(&table.0, &inner.0));
+        assert!(table.has_function("add"));
+        assert!(!inner.has_function("add"));
+    }
+}
@@ -1,9 +1,11 @@
 pub mod api;
 pub mod array;
 pub mod crypto;
+pub mod csv;
 pub mod dev;
 pub mod math;
 pub mod network;
+pub mod stats;
 pub mod str;
 pub mod system;
 pub mod trig;
@@ -34,6 +34,7 @@ const REGISTER: FunctionDefinition = FunctionDefinition {
         ]
     },
     handler: |_function, token, state, args| {
+        state.check_writable(token)?;
         let name = args.get("name").required().as_string();
         let base_url = args.get("base_url").required().as_string();
 
@@ -60,6 +61,7 @@ const DELETE: FunctionDefinition = FunctionDefinition {
         )]
     },
     handler: |_function, token, state, args| {
+        state.check_writable(token)?;
         let name = args.get("name").required().as_string();
         state.apis.remove(&name);
 
@@ -105,17 +107,70 @@ const CALL: FunctionDefinition = FunctionDefinition {
     },
 };
 
+/// HTTP methods accepted by api_call
+const METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
+const CALL_METHOD: FunctionDefinition = FunctionDefinition {
+    name: "api_call",
+    category: Some("network"),
+    description: "Make a call to a registered API using a given HTTP method, such as POST or PUT",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("name", ExpectedTypes::String),
+            FunctionArgument::new_required("method", ExpectedTypes::String),
+            FunctionArgument::new_required("endpoint", ExpectedTypes::String),
+            FunctionArgument::new_optional("body", ExpectedTypes::String),
+        ]
+    },
+    handler: |_function, token, state, args| {
+        let api_name = args.get("name").required().as_string();
+        let endpoint = args.get("endpoint").required().as_string();
+        let body = args.get("body").optional().map(|b| b.as_string());
+
+        let method = args.get("method").required().as_string().to_uppercase();
+        if !METHODS.contains(&method.as_str()) {
+            return Err(Error::StringFormat {
+                expected_format: format!("HTTP method ({})", METHODS.join(", ")),
+                token: token.clone(),
+            });
+        }
+        let method = reqwest::Method::from_bytes(method.as_bytes()).unwrap();
+
+        match state.apis.get(&api_name) {
+            Some(api) => {
+                match api.request_with_method(
+                    method,
+                    &endpoint,
+                    body,
+                    HashMap::from([("Accept".to_string(), "text/plain".to_string())]),
+                ) {
+                    Ok(result) => Ok(Value::String(result.as_string())),
+                    Err(e) => Err(Error::Network(e, token.clone())),
+                }
+            }
+
+            None => Err(Error::UnknownApi {
+                name: api_name,
+                token: token.clone(),
+            }),
+        }
+    },
+};
+
 /// Register api functions
 pub fn register_functions(table: &mut FunctionTable) {
     table.register(REGISTER);
     table.register(DELETE);
     table.register(LIST);
     table.register(CALL);
+    table.register(CALL_METHOD);
 }
 
 #[cfg(test)]
 mod test_builtin_functions {
     use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
 
     fn hardy_net_test(test: fn() -> Result<Value, Error>) -> Value {
         let results = [test(), test(), test(), test(), test()];
@@ -211,4 +266,108 @@ mod test_builtin_functions {
             .contains("the anus")
         );
     }
+
+    /// Start a local HTTP server answering a single request with its method, authorization and body
+    /// Returns the server's base url
+    fn mock_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            let (head, body) = loop {
+                let n = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..n]);
+
+                let text = String::from_utf8_lossy(&request).to_string();
+                let (head, body) = text.split_once("\r\n\r\n").unwrap_or((&text, ""));
+                let length = head
+                    .lines()
+                    .filter_map(|l| l.split_once(':'))
+                    .find(|(k, _)| k.eq_ignore_ascii_case("content-length"))
+                    .map(|(_, v)| v.trim().parse().unwrap())
+                    .unwrap_or(0);
+                if n == 0 || (text.contains("\r\n\r\n") && body.len() >= length) {
+                    break (head.to_string(), body.to_string());
+                }
+            };
+
+            let method = head.split(' ').next().unwrap();
+            let authorization = head
+                .lines()
+                .filter_map(|l| l.split_once(':'))
+                .find(|(k, _)| k.eq_ignore_ascii_case("authorization"))
+                .map(|(_, v)| v.trim())
+                .unwrap_or("none");
+            let response = format!("{} {} {}", method, authorization, body);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+        });
+
+        format!("http://{}", address)
+    }
+
+    #[test]
+    fn test_call_method() {
+        let mut state = ParserState::new();
+        REGISTER
+            .call(
+                &Token::dummy(""),
+                &mut state,
+                &[
+                    Value::from("mock"),
+                    Value::from(mock_server()),
+                    Value::from("secret"),
+                ],
+            )
+            .unwrap();
+
+        assert_eq!(
+            Value::from("PUT Bearer secret hello"),
+            CALL_METHOD
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[
+                        Value::from("mock"),
+                        Value::from("put"),
+                        Value::from("items/1"),
+                        Value::from("hello"),
+                    ]
+                )
+                .unwrap()
+        );
+
+        assert!(matches!(
+            CALL_METHOD.call(
+                &Token::dummy(""),
+                &mut state,
+                &[
+                    Value::from("mock"),
+                    Value::from("fetch"),
+                    Value::from("items/1")
+                ]
+            ),
+            Err(Error::StringFormat { .. })
+        ));
+        assert!(matches!(
+            CALL_METHOD.call(
+                &Token::dummy(""),
+                &mut state,
+                &[
+                    Value::from("not_registered"),
+                    Value::from("get"),
+                    Value::from("")
+                ]
+            ),
+            Err(Error::UnknownApi { .. })
+        ));
+    }
 }
@@ -2,7 +2,7 @@
 
 use super::*;
 use crate::{
-    value::{ArrayType, IntegerType, Value},
+    value::{ArrayType, IntegerType, ObjectType, Value},
     ExpectedTypes,
 };
 
@@ -42,18 +42,341 @@ const IS_EMPTY: FunctionDefinition = FunctionDefinition {
     },
 };
 
-fn manip_arrayarg(token: &Token, state: &mut ParserState, value: Value) {
+fn manip_arrayarg(token: &Token, state: &mut ParserState, value: Value) -> Result<(), Error> {
     if let Some(value_token) = token.child(2) {
         if value_token.rule() == crate::token::Rule::variable
             && state.variables.contains_key(value_token.text())
         {
+            state.check_writable(token)?;
             state
                 .variables
                 .insert(value_token.text().to_string(), value);
         }
     }
+    Ok(())
 }
 
+const ALL: FunctionDefinition = FunctionDefinition {
+    name: "all",
+    category: Some("arrays"),
+    description: "Returns true if every element of the array is truthy, or if the array is empty",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "input",
+            ExpectedTypes::Array,
+        )]
+    },
+    handler: |_function, _token, _state, args| {
+        let input = args.get("input").required().as_array();
+        Ok(Value::Boolean(input.iter().all(|v| v.as_bool())))
+    },
+};
+
+const ANY: FunctionDefinition = FunctionDefinition {
+    name: "any",
+    category: Some("arrays"),
+    description:
+        "Returns true if any element of the array is truthy, and false if the array is empty",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "input",
+            ExpectedTypes::Array,
+        )]
+    },
+    handler: |_function, _token, _state, args| {
+        let input = args.get("input").required().as_array();
+        Ok(Value::Boolean(input.iter().any(|v| v.as_bool())))
+    },
+};
+
+const FIRST: FunctionDefinition = FunctionDefinition {
+    name: "first",
+    category: Some("arrays"),
+    description: "Returns the first element of an array",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "array",
+            ExpectedTypes::Array,
+        )]
+    },
+    handler: |_function, token, _state, args| match args.get("array").required().as_array().first()
+    {
+        Some(v) => Ok(v.clone()),
+        None => Err(Error::ArrayEmpty(token.clone())),
+    },
+};
+
+const LAST: FunctionDefinition = FunctionDefinition {
+    name: "last",
+    category: Some("arrays"),
+    description: "Returns the last element of an array",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "array",
+            ExpectedTypes::Array,
+        )]
+    },
+    handler: |_function, token, _state, args| match args.get("array").required().as_array().last() {
+        Some(v) => Ok(v.clone()),
+        None => Err(Error::ArrayEmpty(token.clone())),
+    },
+};
+
+/// Read a non-negative count argument
+fn count_arg(token: &Token, n: Value) -> Result<usize, Error> {
+    match n.as_int() {
+        Some(i) if i >= 0 => Ok(i as usize),
+        _ => Err(Error::ValueType {
+            value: n,
+            expected_type: ExpectedTypes::Int,
+            token: token.clone(),
+        }),
+    }
+}
+
+const TAKE: FunctionDefinition = FunctionDefinition {
+    name: "take",
+    category: Some("arrays"),
+    description: "Returns the first n elements of an array",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("array", ExpectedTypes::Array),
+            FunctionArgument::new_required("n", ExpectedTypes::Int),
+        ]
+    },
+    handler: |_function, token, _state, args| {
+        let array = args.get("array").required().as_array();
+        let n = count_arg(token, args.get("n").required())?;
+        Ok(Value::Array(array.into_iter().take(n).collect()))
+    },
+};
+
+const DROP: FunctionDefinition = FunctionDefinition {
+    name: "drop",
+    category: Some("arrays"),
+    description: "Returns all but the first n elements of an array",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("array", ExpectedTypes::Array),
+            FunctionArgument::new_required("n", ExpectedTypes::Int),
+        ]
+    },
+    handler: |_function, token, _state, args| {
+        let array = args.get("array").required().as_array();
+        let n = count_arg(token, args.get("n").required())?;
+        Ok(Value::Array(array.into_iter().skip(n).collect()))
+    },
+};
+
+const CHUNK: FunctionDefinition = FunctionDefinition {
+    name: "chunk",
+    category: Some("arrays"),
+    description: "Splits an array into arrays of [size] elements. The last may be shorter",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("array", ExpectedTypes::Array),
+            FunctionArgument::new_required("size", ExpectedTypes::Int),
+        ]
+    },
+    handler: |_function, token, _state, args| {
+        let array = args.get("array").required().as_array();
+        let size = args.get("size").required();
+        match count_arg(token, size.clone())? {
+            0 => Err(Error::ValueType {
+                value: size,
+                expected_type: ExpectedTypes::Int,
+                token: token.clone(),
+            }),
+            n => Ok(Value::Array(
+                array.chunks(n).map(|c| Value::Array(c.to_vec())).collect(),
+            )),
+        }
+    },
+};
+
+const ROTATE: FunctionDefinition = FunctionDefinition {
+    name: "rotate",
+    category: Some("arrays"),
+    description:
+        "Rotates the elements of an array n places to the right, or to the left if n is negative",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("array", ExpectedTypes::Array),
+            FunctionArgument::new_required("n", ExpectedTypes::Int),
+        ]
+    },
+    handler: |_function, _token, _state, args| {
+        let mut array = args.get("array").required().as_array();
+        if !array.is_empty() {
+            let n = args.get("n").required().as_int().unwrap_or(0);
+            let n = n.rem_euclid(array.len() as IntegerType) as usize;
+            array.rotate_right(n);
+        }
+        Ok(Value::Array(array))
+    },
+};
+
+const SHUFFLE: FunctionDefinition = FunctionDefinition {
+    name: "shuffle",
+    category: Some("arrays"),
+    description: "Returns a copy of an array with its elements in a random order",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "array",
+            ExpectedTypes::Array,
+        )]
+    },
+    handler: |_function, _token, state, args| {
+        use rand::seq::SliceRandom;
+
+        let mut array = args.get("array").required().as_array();
+        state.with_rng(|rng| array.shuffle(rng));
+        Ok(Value::Array(array))
+    },
+};
+
+const SAMPLE: FunctionDefinition = FunctionDefinition {
+    name: "sample",
+    category: Some("arrays"),
+    description: "Returns k elements chosen at random from an array, without replacement",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("array", ExpectedTypes::Array),
+            FunctionArgument::new_required("k", ExpectedTypes::Int),
+        ]
+    },
+    handler: |_function, token, state, args| {
+        let array = args.get("array").required().as_array();
+        let k = args.get("k").required();
+        let n = k.as_int().unwrap();
+        if n < 0 || n as usize > array.len() {
+            return Err(Error::ValueType {
+                value: k,
+                expected_type: ExpectedTypes::Int,
+                token: token.clone(),
+            });
+        }
+
+        let indices = state.with_rng(|rng| rand::seq::index::sample(rng, array.len(), n as usize));
+        Ok(Value::Array(
+            indices.into_iter().map(|i| array[i].clone()).collect(),
+        ))
+    },
+};
+
+/// Find the index of the first element comparing as `target` against every other
+///
+/// # Arguments
+/// * `token` - Source token, for errors
+/// * `array` - Elements to search
+/// * `target` - Ordering::Less for the smallest element, Ordering::Greater for the largest
+fn extreme_index(
+    token: &Token,
+    array: &ArrayType,
+    target: std::cmp::Ordering,
+) -> Result<Value, Error> {
+    if array.is_empty() {
+        return Err(Error::ArrayEmpty(token.clone()));
+    }
+
+    let mut index = 0;
+    for (i, element) in array.iter().enumerate().skip(1) {
+        if element.cmp(&array[index]) == target {
+            index = i;
+        }
+    }
+    Ok(Value::Integer(index as IntegerType))
+}
+
+const ARGMIN: FunctionDefinition = FunctionDefinition {
+    name: "argmin",
+    category: Some("arrays"),
+    description: "Returns the index of the smallest element in an array",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "array",
+            ExpectedTypes::Array,
+        )]
+    },
+    handler: |_function, token, _state, args| {
+        extreme_index(
+            token,
+            &args.get("array").required().as_array(),
+            std::cmp::Ordering::Less,
+        )
+    },
+};
+
+const ARGMAX: FunctionDefinition = FunctionDefinition {
+    name: "argmax",
+    category: Some("arrays"),
+    description: "Returns the index of the largest element in an array",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "array",
+            ExpectedTypes::Array,
+        )]
+    },
+    handler: |_function, token, _state, args| {
+        extreme_index(
+            token,
+            &args.get("array").required().as_array(),
+            std::cmp::Ordering::Greater,
+        )
+    },
+};
+
+const GROUP_BY: FunctionDefinition = FunctionDefinition {
+    name: "group_by",
+    category: Some("arrays"),
+    description: "Groups the elements of an array by the key returned from the named user function",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("array", ExpectedTypes::Array),
+            FunctionArgument::new_required("keyfunc", ExpectedTypes::String),
+        ]
+    },
+    handler: |_function, token, state, args| {
+        let name = args.get("keyfunc").required().as_string();
+        let f = match state.user_functions.get(&name) {
+            Some(f) => f.clone(),
+            None => {
+                return Err(Error::FunctionName {
+                    name,
+                    token: token.clone(),
+                })
+            }
+        };
+        if f.arguments().len() != 1 {
+            return Err(Error::FunctionArguments {
+                min: 1,
+                max: 1,
+                signature: f.signature(),
+                token: token.clone(),
+            });
+        }
+
+        let mut result = ObjectType::new();
+        for value in args.get("array").required().as_array() {
+            let mut inner_state = state
+                .spawn_inner()
+                .ok_or_else(|| Error::StackOverflow(token.clone()))?;
+            inner_state
+                .variables
+                .insert(f.arguments()[0].clone(), value.clone());
+            let key = inner_state.eval(f.definition())?;
+
+            match result.get_mut(&key) {
+                Some(Value::Array(group)) => group.push(value),
+                _ => {
+                    result.insert(key, Value::Array(vec![value]));
+                }
+            }
+        }
+        Ok(Value::Object(result))
+    },
+};
+
 const POP: FunctionDefinition = FunctionDefinition {
     name: "pop",
     category: Some("arrays"),
@@ -68,7 +391,7 @@ const POP: FunctionDefinition = FunctionDefinition {
         let mut array = args.get("array").required().as_array();
 
         if let Some(element) = array.pop() {
-            manip_arrayarg(token, state, Value::from(array));
+            manip_arrayarg(token, state, Value::from(array))?;
             Ok(element)
         } else {
             Err(Error::ArrayEmpty(token.clone()))
@@ -89,9 +412,10 @@ const PUSH: FunctionDefinition = FunctionDefinition {
     handler: |_function, token, state, args| {
         let mut array = args.get("array").required().as_array();
         let element = args.get("element").required();
+        state.check_array_length(array.len() + 1, token)?;
 
         array.push(element);
-        manip_arrayarg(token, state, Value::from(array.clone()));
+        manip_arrayarg(token, state, Value::from(array.clone()))?;
         Ok(Value::from(array))
     },
 };
@@ -152,7 +476,7 @@ const REMOVE: FunctionDefinition = FunctionDefinition {
             })
         } else {
             let element = input.remove(index as usize);
-            manip_arrayarg(token, state, Value::from(input));
+            manip_arrayarg(token, state, Value::from(input))?;
             Ok(element)
         }
     },
@@ -206,92 +530,447 @@ const MERGE: FunctionDefinition = FunctionDefinition {
             FunctionArgument::new_plural("inputs", ExpectedTypes::Any, false),
         ]
     },
-    handler: |_function, _token, _state, args| match args.get("target").required() {
+    handler: |_function, token, state, args| match args.get("target").required() {
         Value::Object(mut v) => {
             for arg in args.get("inputs").plural() {
-                v.extend(arg.as_object());
+                let arg = arg.as_object();
+                let added = arg.keys().filter(|k| !v.contains_key(k)).count();
+                state.check_array_length(v.len() + added, token)?;
+                v.extend(arg);
             }
             Ok(Value::Object(v))
         }
 
         _ => {
+            let inputs: Vec<ArrayType> = args
+                .get("inputs")
+                .plural()
+                .iter()
+                .map(|arg| arg.as_array())
+                .collect();
             let mut result: ArrayType = args.get("target").required().as_array();
-            for arg in args.get("inputs").plural() {
-                result.append(&mut arg.as_array());
+            state.check_array_length(
+                result.len() + inputs.iter().map(|a| a.len()).sum::<usize>(),
+                token,
+            )?;
+
+            for mut input in inputs {
+                result.append(&mut input);
             }
             Ok(Value::Array(result))
         }
     },
 };
 
-const KEYS: FunctionDefinition = FunctionDefinition {
-    name: "keys",
+/// Recursively merge source into target
+/// Nested objects are combined, and any other conflicting value is taken from source
+///
+/// # Arguments
+/// * `target` - Object to merge into
+/// * `source` - Object to merge from
+fn deep_merge(mut target: ObjectType, source: ObjectType) -> ObjectType {
+    for (key, value) in source {
+        let merged = match (target.remove(&key), value) {
+            (Some(Value::Object(t)), Value::Object(s)) => Value::Object(deep_merge(t, s)),
+            (_, value) => value,
+        };
+        target.insert(key, merged);
+    }
+    target
+}
+
+const MERGE_DEEP: FunctionDefinition = FunctionDefinition {
+    name: "merge_deep",
     category: Some("arrays"),
-    description: "Get a list of keys in the object or array",
-    arguments: || vec![FunctionArgument::new("input", ExpectedTypes::Any, false)],
+    description: "Recursively merge all given objects, combining nested objects",
+    arguments: || {
+        vec![
+            FunctionArgument::new("target", ExpectedTypes::Object, false),
+            FunctionArgument::new_plural("inputs", ExpectedTypes::Object, false),
+        ]
+    },
     handler: |_function, _token, _state, args| {
-        let mut a = args
-            .get("input")
-            .required()
-            .as_object()
-            .keys()
-            .cloned()
-            .collect::<ArrayType>();
-        a.sort();
-        Ok(Value::Array(a))
+        let mut result = args.get("target").required().as_object();
+        for arg in args.get("inputs").plural() {
+            result = deep_merge(result, arg.as_object());
+        }
+        Ok(Value::Object(result))
     },
 };
 
-const VALUES: FunctionDefinition = FunctionDefinition {
-    name: "values",
+/// Quote a field for a delimited table if it contains special characters
+///
+/// # Arguments
+/// * `field` - Field contents
+/// * `delimiter` - Field delimiter
+fn delimited_field(field: &str, delimiter: &str) -> String {
+    if field.contains(delimiter) || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+const EXPORT: FunctionDefinition = FunctionDefinition {
+    name: "export",
     category: Some("arrays"),
-    description: "Get a list of values in the object or array",
-    arguments: || vec![FunctionArgument::new("input", ExpectedTypes::Any, false)],
+    description: "Export an array of objects as a delimited table, with a header row",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("input", ExpectedTypes::Array),
+            FunctionArgument::new_optional("delimiter", ExpectedTypes::String),
+        ]
+    },
     handler: |_function, _token, _state, args| {
-        let mut a = args
+        let rows: Vec<ObjectType> = args
             .get("input")
             .required()
-            .as_object()
-            .values()
-            .cloned()
-            .collect::<ArrayType>();
-        a.sort();
-        Ok(Value::Array(a))
+            .as_array()
+            .iter()
+            .map(|r| r.as_object())
+            .collect();
+        let delimiter = args
+            .get("delimiter")
+            .optional_or(Value::from(","))
+            .as_string();
+
+        // Union of all keys, sorted for determinism
+        let mut header: ArrayType = rows.iter().flat_map(|r| r.keys().cloned()).collect();
+        header.sort();
+        header.dedup();
+
+        let mut lines = vec![header
+            .iter()
+            .map(|k| delimited_field(&k.as_string(), &delimiter))
+            .collect::<Vec<String>>()
+            .join(&delimiter)];
+        for row in rows {
+            lines.push(
+                header
+                    .iter()
+                    .map(|k| match row.get(k) {
+                        Some(v) => delimited_field(&v.as_string(), &delimiter),
+                        None => "".to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(&delimiter),
+            );
+        }
+
+        Ok(Value::String(lines.join("\n")))
     },
 };
 
-/// Register array functions
-pub fn register_functions(table: &mut FunctionTable) {
-    table.register(LEN);
-    table.register(IS_EMPTY);
-    table.register(POP);
-    table.register(PUSH);
-    table.register(DEQUEUE);
-    table.register(ENQUEUE);
-    table.register(REMOVE);
-    table.register(ELEMENT);
-    table.register(MERGE);
-    table.register(KEYS);
-    table.register(VALUES);
-}
+const INVERT: FunctionDefinition = FunctionDefinition {
+    name: "invert",
+    category: Some("arrays"),
+    description: "Swap the keys and values of an object",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "input",
+            ExpectedTypes::Object,
+        )]
+    },
+    handler: |_function, _token, _state, args| {
+        let input = args.get("input").required().as_object();
 
-#[cfg(test)]
-mod test_builtin_functions {
-    use std::collections::HashMap;
+        // Keys are visited in sorted order so that duplicate values
+        // deterministically map to the largest key
+        let mut keys = input.keys().collect::<Vec<&Value>>();
+        keys.sort();
 
-    use super::*;
+        let mut result = ObjectType::new();
+        for key in keys {
+            result.insert(input[key].clone(), key.clone());
+        }
+        Ok(Value::Object(result))
+    },
+};
 
-    #[test]
-    fn test_len() {
-        let mut state = ParserState::new();
+const WHERE: FunctionDefinition = FunctionDefinition {
+    name: "where",
+    category: Some("arrays"),
+    description: "Return the objects in an array whose given key is equal to the given value",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("input", ExpectedTypes::Array),
+            FunctionArgument::new_required("key", ExpectedTypes::Any),
+            FunctionArgument::new_required("value", ExpectedTypes::Any),
+        ]
+    },
+    handler: |_function, _token, _state, args| {
+        let key = args.get("key").required();
+        let value = args.get("value").required();
+        Ok(Value::Array(
+            args.get("input")
+                .required()
+                .as_array()
+                .into_iter()
+                .filter(|r| match r {
+                    Value::Object(o) => o.get(&key) == Some(&value),
+                    _ => false,
+                })
+                .collect(),
+        ))
+    },
+};
 
-        assert_eq!(
-            Value::Integer(1),
-            LEN.call(
-                &Token::dummy(""),
-                &mut state,
-                &[Value::Array(vec![Value::Integer(5),])]
-            )
+const PLUCK: FunctionDefinition = FunctionDefinition {
+    name: "pluck",
+    category: Some("arrays"),
+    description: "Return the value of the given key from each object in an array, skipping objects without it",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("input", ExpectedTypes::Array),
+            FunctionArgument::new_required("key", ExpectedTypes::Any),
+        ]
+    },
+    handler: |_function, _token, _state, args| {
+        let key = args.get("key").required();
+        Ok(Value::Array(
+            args.get("input")
+                .required()
+                .as_array()
+                .iter()
+                .filter_map(|r| match r {
+                    Value::Object(o) => o.get(&key).cloned(),
+                    _ => None,
+                })
+                .collect(),
+        ))
+    },
+};
+
+const ORDER_BY: FunctionDefinition = FunctionDefinition {
+    name: "order_by",
+    category: Some("arrays"),
+    description: "Sort an array of objects by the value of a given key, 'asc' (default) or 'desc'. Objects without the key sort first",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("input", ExpectedTypes::Array),
+            FunctionArgument::new_required("key", ExpectedTypes::Any),
+            FunctionArgument::new_optional("direction", ExpectedTypes::String),
+        ]
+    },
+    handler: |_function, token, _state, args| {
+        let key = args.get("key").required();
+        let direction = args.get("direction").optional_or(Value::from("asc"));
+        let descending = match direction.as_string().to_lowercase().as_str() {
+            "asc" => false,
+            "desc" => true,
+            _ => {
+                return Err(Error::Range {
+                    value: direction,
+                    token: token.clone(),
+                })
+            }
+        };
+
+        let field = |r: &Value| match r {
+            Value::Object(o) => o.get(&key).cloned(),
+            _ => None,
+        };
+        let mut rows = args.get("input").required().as_array();
+        rows.sort_by(|a, b| {
+            let ordering = field(a)
+                .partial_cmp(&field(b))
+                .unwrap_or(std::cmp::Ordering::Equal);
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        Ok(Value::Array(rows))
+    },
+};
+
+const JOIN: FunctionDefinition = FunctionDefinition {
+    name: "join",
+    category: Some("arrays"),
+    description:
+        "Inner-join two arrays of objects on a shared key, merging each matching pair of objects",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("left", ExpectedTypes::Array),
+            FunctionArgument::new_required("right", ExpectedTypes::Array),
+            FunctionArgument::new_required("key", ExpectedTypes::Any),
+        ]
+    },
+    handler: |_function, token, state, args| {
+        let key = args.get("key").required();
+        let objects = |name: &str| -> Vec<ObjectType> {
+            args.get(name)
+                .required()
+                .as_array()
+                .iter()
+                .filter(|r| r.is_object())
+                .map(|r| r.as_object())
+                .collect()
+        };
+        let right = objects("right");
+
+        let mut result = ArrayType::new();
+        for l in objects("left") {
+            let Some(id) = l.get(&key) else {
+                continue;
+            };
+            for r in right.iter().filter(|r| r.get(&key) == Some(id)) {
+                state.check_array_length(result.len() + 1, token)?;
+                let mut row = l.clone();
+                row.extend(r.iter().map(|(k, v)| (k.clone(), v.clone())));
+                result.push(Value::Object(row));
+            }
+        }
+        Ok(Value::Array(result))
+    },
+};
+
+const KEYS: FunctionDefinition = FunctionDefinition {
+    name: "keys",
+    category: Some("arrays"),
+    description: "Get a list of keys in the object or array",
+    arguments: || vec![FunctionArgument::new("input", ExpectedTypes::Any, false)],
+    handler: |_function, _token, _state, args| {
+        let mut a = args
+            .get("input")
+            .required()
+            .as_object()
+            .keys()
+            .cloned()
+            .collect::<ArrayType>();
+        a.sort();
+        Ok(Value::Array(a))
+    },
+};
+
+const VALUES: FunctionDefinition = FunctionDefinition {
+    name: "values",
+    category: Some("arrays"),
+    description: "Get a list of values in the object or array",
+    arguments: || vec![FunctionArgument::new("input", ExpectedTypes::Any, false)],
+    handler: |_function, _token, _state, args| {
+        let mut a = args
+            .get("input")
+            .required()
+            .as_object()
+            .values()
+            .cloned()
+            .collect::<ArrayType>();
+        a.sort();
+        Ok(Value::Array(a))
+    },
+};
+
+/// Check if a value is of the type named in a schema
+fn schema_type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "any" => true,
+        "number" => value.is_numeric(),
+        _ => value.type_name() == expected,
+    }
+}
+
+const VALIDATE: FunctionDefinition = FunctionDefinition {
+    name: "validate",
+    category: Some("arrays"),
+    description:
+        "Check that an object's keys have the types named in schema, such as {\"age\": \"integer\"}",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("value", ExpectedTypes::Object),
+            FunctionArgument::new_required("schema", ExpectedTypes::Object),
+        ]
+    },
+    handler: |_function, token, _state, args| {
+        let value = args.get("value").required().as_object();
+        let mut schema: Vec<(Value, Value)> = args
+            .get("schema")
+            .required()
+            .as_object()
+            .into_iter()
+            .collect();
+        schema.sort();
+
+        let mut mismatches: Vec<String> = Vec::new();
+        for (key, expected) in schema {
+            let expected = expected.as_string();
+            match value.get(&key) {
+                Some(v) if schema_type_matches(&expected, v) => {}
+                Some(v) => mismatches.push(format!(
+                    "{} should be {} but was {}",
+                    key.as_string(),
+                    expected,
+                    v.type_name()
+                )),
+                None => mismatches.push(format!("{} is missing", key.as_string())),
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(Value::Boolean(true))
+        } else {
+            Err(Error::Validation {
+                reason: mismatches.join(", "),
+                token: token.clone(),
+            })
+        }
+    },
+};
+
+/// Register array functions
+pub fn register_functions(table: &mut FunctionTable) {
+    table.register(LEN);
+    table.register(IS_EMPTY);
+    table.register(ALL);
+    table.register(ANY);
+    table.register(FIRST);
+    table.register(LAST);
+    table.register(TAKE);
+    table.register(DROP);
+    table.register(CHUNK);
+    table.register(ROTATE);
+    table.register(SHUFFLE);
+    table.register(SAMPLE);
+    table.register(ARGMIN);
+    table.register(ARGMAX);
+    table.register(GROUP_BY);
+    table.register(POP);
+    table.register(PUSH);
+    table.register(DEQUEUE);
+    table.register(ENQUEUE);
+    table.register(REMOVE);
+    table.register(ELEMENT);
+    table.register(MERGE);
+    table.register(MERGE_DEEP);
+    table.register(EXPORT);
+    table.register(INVERT);
+    table.register(WHERE);
+    table.register(PLUCK);
+    table.register(ORDER_BY);
+    table.register(JOIN);
+    table.register(KEYS);
+    table.register(VALUES);
+    table.register(VALIDATE);
+}
+
+#[cfg(test)]
+mod test_builtin_functions {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_len() {
+        let mut state = ParserState::new();
+
+        assert_eq!(
+            Value::Integer(1),
+            LEN.call(
+                &Token::dummy(""),
+                &mut state,
+                &[Value::Array(vec![Value::Integer(5),])]
+            )
             .unwrap()
         );
         assert_eq!(
@@ -484,6 +1163,593 @@ mod test_builtin_functions {
                 )
                 .unwrap()
         );
+
+        // The limit is enforced by the builtin itself, before the merged array is built
+        state.set_max_array_length(Some(3));
+        assert!(matches!(
+            MERGE.call(
+                &Token::dummy(""),
+                &mut state,
+                &[
+                    Value::Array(vec![Value::Integer(1), Value::Integer(2)]),
+                    Value::Array(vec![Value::Integer(3), Value::Integer(4)])
+                ]
+            ),
+            Err(Error::Overflow(_))
+        ));
+        assert!(matches!(
+            MERGE.call(
+                &Token::dummy(""),
+                &mut state,
+                &[
+                    Value::Object(HashMap::from([(Value::from("a"), Value::Integer(1))])),
+                    Value::Object(HashMap::from([
+                        (Value::from("b"), Value::Integer(2)),
+                        (Value::from("c"), Value::Integer(3)),
+                        (Value::from("d"), Value::Integer(4))
+                    ]))
+                ]
+            ),
+            Err(Error::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_merge_deep() {
+        let mut state = ParserState::new();
+
+        let target = Value::Object(HashMap::from([
+            (
+                Value::from("a"),
+                Value::Object(HashMap::from([
+                    (Value::from("x"), Value::Integer(1)),
+                    (Value::from("y"), Value::Integer(2)),
+                ])),
+            ),
+            (
+                Value::from("b"),
+                Value::Object(HashMap::from([(Value::from("z"), Value::Integer(3))])),
+            ),
+        ]));
+        let source = Value::Object(HashMap::from([
+            (
+                Value::from("a"),
+                Value::Object(HashMap::from([
+                    (Value::from("y"), Value::Integer(5)),
+                    (Value::from("w"), Value::Integer(6)),
+                ])),
+            ),
+            (Value::from("b"), Value::Integer(4)),
+        ]));
+
+        let result = MERGE_DEEP
+            .call(&Token::dummy(""), &mut state, &[target, source])
+            .unwrap()
+            .as_object();
+
+        // Nested objects are combined
+        let a = result.get(&Value::from("a")).unwrap().as_object();
+        assert_eq!(Value::Integer(1), a[&Value::from("x")]);
+        assert_eq!(Value::Integer(5), a[&Value::from("y")]);
+        assert_eq!(Value::Integer(6), a[&Value::from("w")]);
+
+        // Scalars overwrite objects
+        assert_eq!(Value::Integer(4), result[&Value::from("b")]);
+    }
+
+    #[test]
+    fn test_export() {
+        let mut state = ParserState::new();
+
+        let input = Value::Array(vec![
+            Value::Object(HashMap::from([
+                (Value::from("name"), Value::from("Bob")),
+                (Value::from("age"), Value::Integer(30)),
+            ])),
+            Value::Object(HashMap::from([
+                (Value::from("name"), Value::from("Smith, Jane")),
+                (Value::from("city"), Value::from("Paris")),
+            ])),
+        ]);
+
+        assert_eq!(
+            Value::from("age,city,name\n30,,Bob\n,Paris,\"Smith, Jane\""),
+            EXPORT
+                .call(&Token::dummy(""), &mut state, &[input.clone()])
+                .unwrap()
+        );
+        assert_eq!(
+            Value::from("age\tcity\tname\n30\t\tBob\n\tParis\tSmith, Jane"),
+            EXPORT
+                .call(&Token::dummy(""), &mut state, &[input, Value::from("\t")])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invert() {
+        let mut state = ParserState::new();
+
+        let result = INVERT
+            .call(
+                &Token::dummy(""),
+                &mut state,
+                &[Value::Object(HashMap::from([
+                    (Value::from("a"), Value::Integer(1)),
+                    (Value::from("b"), Value::Integer(2)),
+                    (Value::from("c"), Value::Integer(2)),
+                ]))],
+            )
+            .unwrap()
+            .as_object();
+        assert_eq!(2, result.len());
+        assert_eq!(Value::from("a"), result[&Value::Integer(1)]);
+        assert_eq!(Value::from("c"), result[&Value::Integer(2)]);
+    }
+
+    #[test]
+    fn test_where() {
+        let mut state = ParserState::new();
+        let record = |name: &str, age: IntegerType| {
+            Value::Object(HashMap::from([
+                (Value::from("name"), Value::from(name)),
+                (Value::from("age"), Value::Integer(age)),
+            ]))
+        };
+
+        assert_eq!(
+            Value::Array(vec![record("a", 30), record("c", 30)]),
+            WHERE
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[
+                        Value::Array(vec![
+                            record("a", 30),
+                            record("b", 25),
+                            Value::Integer(30),
+                            record("c", 30)
+                        ]),
+                        Value::from("age"),
+                        Value::Integer(30)
+                    ]
+                )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_pluck() {
+        let mut state = ParserState::new();
+
+        assert_eq!(
+            Value::Array(vec![Value::from("a"), Value::from("c")]),
+            PLUCK
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[
+                        Value::Array(vec![
+                            Value::Object(HashMap::from([(Value::from("name"), Value::from("a"))])),
+                            Value::Object(HashMap::from([(Value::from("age"), Value::Integer(5))])),
+                            Value::Object(HashMap::from([(Value::from("name"), Value::from("c"))])),
+                        ]),
+                        Value::from("name")
+                    ]
+                )
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_order_by() {
+        let mut state = ParserState::new();
+        let record = |age: IntegerType| {
+            Value::Object(HashMap::from([(Value::from("age"), Value::Integer(age))]))
+        };
+        let input = Value::Array(vec![record(30), record(10), record(20)]);
+
+        assert_eq!(
+            Value::Array(vec![record(10), record(20), record(30)]),
+            ORDER_BY
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[input.clone(), Value::from("age")]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Array(vec![record(30), record(20), record(10)]),
+            ORDER_BY
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[input.clone(), Value::from("age"), Value::from("desc")]
+                )
+                .unwrap()
+        );
+        assert!(matches!(
+            ORDER_BY.call(
+                &Token::dummy(""),
+                &mut state,
+                &[input, Value::from("age"), Value::from("sideways")]
+            ),
+            Err(Error::Range { .. })
+        ));
+    }
+
+    #[test]
+    fn test_join() {
+        let mut state = ParserState::new();
+        let object = |pairs: &[(&str, Value)]| {
+            Value::Object(
+                pairs
+                    .iter()
+                    .map(|(k, v)| (Value::from(*k), v.clone()))
+                    .collect(),
+            )
+        };
+
+        let users = Value::Array(vec![
+            object(&[("id", Value::Integer(1)), ("name", Value::from("a"))]),
+            object(&[("id", Value::Integer(2)), ("name", Value::from("b"))]),
+        ]);
+        let orders = Value::Array(vec![
+            object(&[("id", Value::Integer(2)), ("total", Value::Integer(10))]),
+            object(&[("id", Value::Integer(3)), ("total", Value::Integer(20))]),
+        ]);
+
+        assert_eq!(
+            Value::Array(vec![object(&[
+                ("id", Value::Integer(2)),
+                ("name", Value::from("b")),
+                ("total", Value::Integer(10))
+            ])]),
+            JOIN.call(
+                &Token::dummy(""),
+                &mut state,
+                &[users, orders, Value::from("id")]
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_all_any() {
+        let mut state = ParserState::new();
+        let mixed = Value::Array(vec![Value::Integer(1), Value::Boolean(false)]);
+        let truthy = Value::Array(vec![Value::Integer(1), Value::from("a")]);
+        let empty = Value::Array(vec![]);
+
+        assert_eq!(
+            Value::Boolean(false),
+            ALL.call(&Token::dummy(""), &mut state, &[mixed.clone()])
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Boolean(true),
+            ALL.call(&Token::dummy(""), &mut state, &[truthy.clone()])
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Boolean(true),
+            ALL.call(&Token::dummy(""), &mut state, &[empty.clone()])
+                .unwrap()
+        );
+
+        assert_eq!(
+            Value::Boolean(true),
+            ANY.call(&Token::dummy(""), &mut state, &[mixed]).unwrap()
+        );
+        assert_eq!(
+            Value::Boolean(false),
+            ANY.call(
+                &Token::dummy(""),
+                &mut state,
+                &[Value::Array(vec![Value::Integer(0), Value::Boolean(false)])]
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Value::Boolean(false),
+            ANY.call(&Token::dummy(""), &mut state, &[empty]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_first_last() {
+        let mut state = ParserState::new();
+        let array = Value::Array(vec![
+            Value::Integer(1),
+            Value::Integer(2),
+            Value::Integer(3),
+        ]);
+
+        assert_eq!(
+            Value::Integer(1),
+            FIRST
+                .call(&Token::dummy(""), &mut state, &[array.clone()])
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Integer(3),
+            LAST.call(&Token::dummy(""), &mut state, &[array]).unwrap()
+        );
+
+        for f in [FIRST, LAST] {
+            assert_eq!(
+                true,
+                matches!(
+                    f.call(&Token::dummy(""), &mut state, &[Value::Array(vec![])]),
+                    Err(Error::ArrayEmpty(_))
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_take_drop() {
+        let mut state = ParserState::new();
+        let array = Value::Array(vec![
+            Value::Integer(1),
+            Value::Integer(2),
+            Value::Integer(3),
+        ]);
+
+        assert_eq!(
+            Value::Array(vec![Value::Integer(1), Value::Integer(2)]),
+            TAKE.call(
+                &Token::dummy(""),
+                &mut state,
+                &[array.clone(), Value::Integer(2)]
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Value::Array(vec![Value::Integer(3)]),
+            DROP.call(
+                &Token::dummy(""),
+                &mut state,
+                &[array.clone(), Value::Integer(2)]
+            )
+            .unwrap()
+        );
+
+        assert_eq!(
+            array.clone(),
+            TAKE.call(
+                &Token::dummy(""),
+                &mut state,
+                &[array.clone(), Value::Integer(10)]
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Value::Array(vec![]),
+            DROP.call(
+                &Token::dummy(""),
+                &mut state,
+                &[array.clone(), Value::Integer(10)]
+            )
+            .unwrap()
+        );
+
+        for f in [TAKE, DROP] {
+            assert_eq!(
+                true,
+                matches!(
+                    f.call(
+                        &Token::dummy(""),
+                        &mut state,
+                        &[array.clone(), Value::Integer(-1)]
+                    ),
+                    Err(Error::ValueType { .. })
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_chunk() {
+        let mut state = ParserState::new();
+        let array = |n: IntegerType| Value::Array((1..=n).map(Value::Integer).collect());
+
+        assert_eq!(
+            Value::Array(vec![
+                array(2),
+                Value::Array(vec![Value::Integer(3), Value::Integer(4)])
+            ]),
+            CHUNK
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[array(4), Value::Integer(2)]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Array(vec![
+                array(2),
+                Value::Array(vec![Value::Integer(3), Value::Integer(4)]),
+                Value::Array(vec![Value::Integer(5)])
+            ]),
+            CHUNK
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[array(5), Value::Integer(2)]
+                )
+                .unwrap()
+        );
+
+        for size in [0, -1] {
+            assert_eq!(
+                true,
+                matches!(
+                    CHUNK.call(
+                        &Token::dummy(""),
+                        &mut state,
+                        &[array(5), Value::Integer(size)]
+                    ),
+                    Err(Error::ValueType { .. })
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut state = ParserState::new();
+        let array =
+            |v: &[IntegerType]| Value::Array(v.iter().cloned().map(Value::Integer).collect());
+
+        let input = array(&[1, 2, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let mut shuffled = SHUFFLE
+            .call(&Token::dummy(""), &mut state, &[input.clone()])
+            .unwrap()
+            .as_array();
+        shuffled.sort();
+        assert_eq!(input.as_array(), shuffled);
+
+        for input in [array(&[]), array(&[1])] {
+            assert_eq!(
+                input,
+                SHUFFLE
+                    .call(&Token::dummy(""), &mut state, &[input.clone()])
+                    .unwrap()
+            );
+        }
+
+        // Seeded shuffles are reproducible
+        let mut seeded = |seed| {
+            state.set_rng_seed(Some(seed));
+            state.eval("shuffle([1, 2, 3, 4, 5, 6, 7, 8])").unwrap()
+        };
+        assert_eq!(seeded(7), seeded(7));
+    }
+
+    #[test]
+    fn test_sample() {
+        let mut state = ParserState::new();
+        let array =
+            |v: &[IntegerType]| Value::Array(v.iter().cloned().map(Value::Integer).collect());
+        let input = array(&[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        for k in [0, 3, 8] {
+            let mut result = SAMPLE
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[input.clone(), Value::Integer(k)],
+                )
+                .unwrap()
+                .as_array();
+            assert_eq!(k as usize, result.len());
+            assert!(result.iter().all(|e| input.as_array().contains(e)));
+
+            result.sort();
+            result.dedup();
+            assert_eq!(k as usize, result.len());
+        }
+
+        for k in [9, -1] {
+            assert!(matches!(
+                SAMPLE.call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[input.clone(), Value::Integer(k)]
+                ),
+                Err(Error::ValueType { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_argmin_argmax() {
+        let mut state = ParserState::new();
+
+        assert_token_value_stateful!("argmin([3, 1, 4, 1, 5])", Value::Integer(1), &mut state);
+        assert_token_value_stateful!("argmax([3, 1, 5, 4, 5])", Value::Integer(2), &mut state);
+        assert_token_value_stateful!("argmax([2.5, 1, 3])", Value::Integer(2), &mut state);
+        assert_token_value_stateful!("argmin([7])", Value::Integer(0), &mut state);
+        assert_token_error_stateful!("argmin([])", ArrayEmpty, &mut state);
+        assert_token_error_stateful!("argmax([])", ArrayEmpty, &mut state);
+    }
+
+    #[test]
+    fn test_group_by() {
+        let mut state = ParserState::new();
+
+        Token::new("parity(x) = x%2", &mut state).unwrap();
+        assert_token_value_stateful!(
+            "group_by([1,2,3,4], 'parity')",
+            Value::Object(ObjectType::from([
+                (
+                    Value::Integer(0),
+                    Value::Array(vec![Value::Integer(2), Value::Integer(4)])
+                ),
+                (
+                    Value::Integer(1),
+                    Value::Array(vec![Value::Integer(1), Value::Integer(3)])
+                ),
+            ])),
+            &mut state
+        );
+        assert_token_value_stateful!(
+            "group_by([], 'parity')",
+            Value::Object(ObjectType::new()),
+            &mut state
+        );
+        assert_token_error_stateful!("group_by([1], 'nope')", FunctionName, &mut state);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let mut state = ParserState::new();
+        let array =
+            |v: &[IntegerType]| Value::Array(v.iter().cloned().map(Value::Integer).collect());
+
+        assert_eq!(
+            array(&[4, 1, 2, 3]),
+            ROTATE
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[array(&[1, 2, 3, 4]), Value::Integer(1)]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            array(&[2, 3, 4, 1]),
+            ROTATE
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[array(&[1, 2, 3, 4]), Value::Integer(-1)]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            array(&[3, 4, 1, 2]),
+            ROTATE
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[array(&[1, 2, 3, 4]), Value::Integer(10)]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            array(&[]),
+            ROTATE
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[array(&[]), Value::Integer(3)]
+                )
+                .unwrap()
+        );
     }
 
     #[test]
@@ -528,4 +1794,45 @@ mod test_builtin_functions {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn test_validate() {
+        let mut state = ParserState::new();
+        let schema = Value::Object(HashMap::from([
+            (Value::from("name"), Value::from("string")),
+            (Value::from("age"), Value::from("integer")),
+        ]));
+
+        assert_eq!(
+            Value::Boolean(true),
+            VALIDATE
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[
+                        Value::Object(HashMap::from([
+                            (Value::from("name"), Value::from("bob")),
+                            (Value::from("age"), Value::Integer(32)),
+                        ])),
+                        schema.clone()
+                    ]
+                )
+                .unwrap()
+        );
+
+        match VALIDATE.call(
+            &Token::dummy(""),
+            &mut state,
+            &[
+                Value::Object(HashMap::from([(Value::from("age"), Value::from("old"))])),
+                schema,
+            ],
+        ) {
+            Err(Error::Validation { reason, .. }) => assert_eq!(
+                "age should be integer but was string, name is missing",
+                reason
+            ),
+            _ => panic!("expected a validation error"),
+        }
+    }
 }
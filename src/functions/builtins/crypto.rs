@@ -57,9 +57,8 @@ const CHOOSE: FunctionDefinition = FunctionDefinition {
             false,
         )]
     },
-    handler: |_function, _token, _state, args| {
-        let mut rng = rand::thread_rng();
-        let arg = rng.gen_range(0..args.len());
+    handler: |_function, _token, state, args| {
+        let arg = state.with_rng(|rng| rng.gen_range(0..args.len()));
         Ok(args[arg].clone())
     },
 };
@@ -72,19 +71,20 @@ const RAND : FunctionDefinition = FunctionDefinition {
         FunctionArgument::new_optional("m", ExpectedTypes::Int),
         FunctionArgument::new_optional("n", ExpectedTypes::Int)
     ],
-    handler: |_function, _token, _state, args| {
-        let mut rng = rand::thread_rng();
+    handler: |_function, _token, state, args| {
         let m = args.get("m").optional_or(Value::Integer(0)).as_int().unwrap_or(0);
         let n = args.get("n").optional_or(Value::Integer(0)).as_int().unwrap_or(0);
 
-        if m+n == 0 {
-            // Generate a float between 0 and 1
-            Ok(Value::Float(rng.gen()))
-        } else if n>m {
-            Ok(Value::Integer(rng.gen_range(m..n)))
-        } else {
-            Ok(Value::Integer(rng.gen_range(n..m)))
-        }
+        state.with_rng(|rng| {
+            if m+n == 0 {
+                // Generate a float between 0 and 1
+                Ok(Value::Float(rng.gen()))
+            } else if n>m {
+                Ok(Value::Integer(rng.gen_range(m..n)))
+            } else {
+                Ok(Value::Integer(rng.gen_range(n..m)))
+            }
+        })
     }
 };
 
@@ -199,4 +199,25 @@ mod test_builtin_table {
             );
         }
     }
+
+    #[test]
+    fn test_rand_seeded() {
+        let mut a = ParserState::new();
+        let mut b = ParserState::new();
+        a.set_rng_seed(Some(42));
+        b.set_rng_seed(Some(42));
+
+        for _ in 0..10 {
+            assert_eq!(
+                RAND.call(&Token::dummy(""), &mut a, &[]).unwrap(),
+                RAND.call(&Token::dummy(""), &mut b, &[]).unwrap()
+            );
+            assert_eq!(
+                RAND.call(&Token::dummy(""), &mut a, &[Value::Integer(100)])
+                    .unwrap(),
+                RAND.call(&Token::dummy(""), &mut b, &[Value::Integer(100)])
+                    .unwrap()
+            );
+        }
+    }
 }
@@ -0,0 +1,140 @@
+//! Builtin functions for CSV data
+
+use super::*;
+use crate::{value::Value, ExpectedTypes};
+
+/// Split a string into rows of fields, as described by RFC 4180
+/// Returns None if a quote is left open or appears inside an unquoted field
+///
+/// # Arguments
+/// * `input` - CSV-formatted string
+fn parse_csv(input: &str) -> Option<Vec<Vec<String>>> {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut after_quote = false;
+
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    field.push('"');
+                } else {
+                    in_quotes = false;
+                    after_quote = true;
+                }
+            } else {
+                field.push(c);
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() && !after_quote => in_quotes = true,
+            ',' => {
+                row.push(std::mem::take(&mut field));
+                after_quote = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+                after_quote = false;
+            }
+            _ if c == '"' || after_quote => return None,
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return None;
+    }
+
+    // A trailing line break does not start a new row
+    if !field.is_empty() || after_quote || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    Some(rows)
+}
+
+const PARSE_CSV: FunctionDefinition = FunctionDefinition {
+    name: "parse_csv",
+    category: Some("strings"),
+    description: "Parse a CSV string into an array of rows, each an array of string cells",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "input",
+            ExpectedTypes::String,
+        )]
+    },
+    handler: |_function, token, _state, args| match parse_csv(
+        &args.get("input").required().as_string(),
+    ) {
+        Some(rows) => Ok(Value::Array(
+            rows.into_iter()
+                .map(|row| Value::Array(row.into_iter().map(Value::String).collect()))
+                .collect(),
+        )),
+        None => Err(Error::StringFormat {
+            expected_format: "csv".to_string(),
+            token: token.clone(),
+        }),
+    },
+};
+
+/// Register csv functions
+pub fn register_functions(table: &mut FunctionTable) {
+    table.register(PARSE_CSV);
+}
+
+#[cfg(test)]
+mod test_builtin_functions {
+    use super::*;
+
+    fn grid(rows: &[&[&str]]) -> Value {
+        Value::Array(
+            rows.iter()
+                .map(|row| Value::Array(row.iter().map(|s| Value::from(*s)).collect()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let mut state = ParserState::new();
+        let mut call =
+            |input: &str| PARSE_CSV.call(&Token::dummy(""), &mut state, &[Value::from(input)]);
+
+        assert_eq!(
+            grid(&[&["a", "b", "c"], &["1", "2", "3"]]),
+            call("a,b,c\r\n1,2,3\r\n").unwrap()
+        );
+        assert_eq!(grid(&[&["a", "", "c"]]), call("a,,c").unwrap());
+        assert_eq!(grid(&[]), call("").unwrap());
+
+        // Quoted fields may contain commas, escaped quotes and line breaks
+        assert_eq!(
+            grid(&[&["name", "note"], &["smith, john", "said \"hi\""]]),
+            call("name,note\n\"smith, john\",\"said \"\"hi\"\"\"").unwrap()
+        );
+        assert_eq!(
+            grid(&[&["1", "line one\nline two"], &["2", ""]]),
+            call("1,\"line one\nline two\"\n2,\"\"").unwrap()
+        );
+
+        assert!(matches!(
+            call("a,\"unterminated"),
+            Err(Error::StringFormat { .. })
+        ));
+        assert!(matches!(
+            call("a,\"quoted\"trailing"),
+            Err(Error::StringFormat { .. })
+        ));
+        assert!(matches!(call("a,b\"c"), Err(Error::StringFormat { .. })));
+    }
+}
@@ -4,6 +4,7 @@ use super::*;
 use crate::value::{IntegerType, Value};
 use crate::ExpectedTypes;
 
+use chrono::{Datelike, Days, NaiveDateTime};
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -19,6 +20,148 @@ const TIME: FunctionDefinition = FunctionDefinition {
     },
 };
 
+const TIME_MS: FunctionDefinition = FunctionDefinition {
+    name: "time_ms",
+    category: None,
+    description: "Returns the current system time in milliseconds since the unix epoch",
+    arguments: Vec::new,
+    handler: |_function, _token, _state, _args| match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(n) => Ok(Value::Integer(n.as_millis() as IntegerType)),
+        Err(_) => Ok(Value::Integer(0)),
+    },
+};
+
+const NOW_MS: FunctionDefinition = FunctionDefinition {
+    name: "now_ms",
+    description:
+        "Returns the current system time in milliseconds since the unix epoch. Alias of time_ms",
+    ..TIME_MS
+};
+
+const ELAPSED: FunctionDefinition = FunctionDefinition {
+    name: "elapsed",
+    category: None,
+    description: "Returns the number of milliseconds since a timestamp returned by now_ms",
+    arguments: || vec![FunctionArgument::new_required("start", ExpectedTypes::Int)],
+    handler: |function, token, state, args| {
+        let now = NOW_MS.call(token, state, &[])?.as_int().unwrap();
+        let start = args.get("start").required().as_int().unwrap();
+        match now.checked_sub(start) {
+            Some(n) => Ok(Value::Integer(n)),
+            None => Err(Error::FunctionArgumentOverflow {
+                arg: 1,
+                signature: function.signature(),
+                token: token.clone(),
+            }),
+        }
+    },
+};
+
+/// Convert a unix timestamp argument into a date
+fn timestamp_to_date(token: &Token, timestamp: &Value) -> Result<NaiveDateTime, Error> {
+    match NaiveDateTime::from_timestamp_opt(timestamp.as_int().unwrap_or(0), 0) {
+        Some(t) => Ok(t),
+        None => Err(Error::Range {
+            value: timestamp.clone(),
+            token: token.clone(),
+        }),
+    }
+}
+
+const WEEKDAY: FunctionDefinition = FunctionDefinition {
+    name: "weekday",
+    category: None,
+    description: "Returns the name of the day of the week for a unix timestamp, in UTC",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "timestamp",
+            ExpectedTypes::Int,
+        )]
+    },
+    handler: |_function, token, _state, args| {
+        let date = timestamp_to_date(token, &args.get("timestamp").required())?;
+        Ok(Value::String(date.format("%A").to_string()))
+    },
+};
+
+const DAY_OF_YEAR: FunctionDefinition = FunctionDefinition {
+    name: "day_of_year",
+    category: None,
+    description: "Returns the day of the year, from 1 to 366, for a unix timestamp, in UTC",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "timestamp",
+            ExpectedTypes::Int,
+        )]
+    },
+    handler: |_function, token, _state, args| {
+        let date = timestamp_to_date(token, &args.get("timestamp").required())?;
+        Ok(Value::Integer(date.ordinal() as IntegerType))
+    },
+};
+
+const ADD_DAYS: FunctionDefinition = FunctionDefinition {
+    name: "add_days",
+    category: None,
+    description: "Returns a unix timestamp n days after the given one, in UTC",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("timestamp", ExpectedTypes::Int),
+            FunctionArgument::new_required("n", ExpectedTypes::Int),
+        ]
+    },
+    handler: |_function, token, _state, args| {
+        let date = timestamp_to_date(token, &args.get("timestamp").required())?;
+        let n = args.get("n").required().as_int().unwrap_or(0);
+        let days = Days::new(n.unsigned_abs());
+        let result = if n < 0 {
+            date.checked_sub_days(days)
+        } else {
+            date.checked_add_days(days)
+        };
+
+        match result {
+            Some(t) => Ok(Value::Integer(t.timestamp())),
+            None => Err(Error::Overflow(token.clone())),
+        }
+    },
+};
+
+const DATE_DIFF: FunctionDefinition = FunctionDefinition {
+    name: "date_diff",
+    category: None,
+    description:
+        "Returns the time from timestamp a to b, in [unit]: days, hours, minutes or seconds",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("a", ExpectedTypes::Int),
+            FunctionArgument::new_required("b", ExpectedTypes::Int),
+            FunctionArgument::new_optional("unit", ExpectedTypes::String),
+        ]
+    },
+    handler: |_function, token, _state, args| {
+        let a = timestamp_to_date(token, &args.get("a").required())?;
+        let b = timestamp_to_date(token, &args.get("b").required())?;
+        let diff = b - a;
+
+        match args
+            .get("unit")
+            .optional_or(Value::from("seconds"))
+            .as_string()
+            .as_str()
+        {
+            "days" => Ok(Value::Integer(diff.num_days())),
+            "hours" => Ok(Value::Integer(diff.num_hours())),
+            "minutes" => Ok(Value::Integer(diff.num_minutes())),
+            "seconds" => Ok(Value::Integer(diff.num_seconds())),
+            _ => Err(Error::StringFormat {
+                expected_format: "days, hours, minutes or seconds".to_string(),
+                token: token.clone(),
+            }),
+        }
+    },
+};
+
 const DEFAULT_TAIL_LINES: IntegerType = 1;
 const TAIL: FunctionDefinition = FunctionDefinition {
     name: "tail",
@@ -84,6 +227,37 @@ const PRETTYJSON: FunctionDefinition = FunctionDefinition {
     },
 };
 
+const COALESCE: FunctionDefinition = FunctionDefinition {
+    name: "coalesce",
+    category: None,
+    description: "Returns the first argument that is neither none nor an empty string",
+    arguments: || {
+        vec![FunctionArgument::new_plural(
+            "values",
+            ExpectedTypes::Any,
+            false,
+        )]
+    },
+    handler: |_function, _token, _state, args| {
+        Ok(args
+            .get("values")
+            .plural()
+            .into_iter()
+            .find(|v| !(v.is_none() || v.is_string() && v.as_string().is_empty()))
+            .unwrap_or(Value::None))
+    },
+};
+
+const TYPE: FunctionDefinition = FunctionDefinition {
+    name: "type",
+    category: None,
+    description: "Returns the name of the type of a value",
+    arguments: || vec![FunctionArgument::new_required("input", ExpectedTypes::Any)],
+    handler: |_function, _token, _state, args| {
+        Ok(Value::from(args.get("input").required().type_name()))
+    },
+};
+
 #[cfg(feature = "encoding-functions")]
 const URLENCODE: FunctionDefinition = FunctionDefinition {
     name: "urlencode",
@@ -176,8 +350,17 @@ const BASE64DECODE: FunctionDefinition = FunctionDefinition {
 /// Register developper functions
 pub fn register_functions(table: &mut FunctionTable) {
     table.register(TIME);
+    table.register(TIME_MS);
+    table.register(NOW_MS);
+    table.register(ELAPSED);
+    table.register(WEEKDAY);
+    table.register(DAY_OF_YEAR);
+    table.register(ADD_DAYS);
+    table.register(DATE_DIFF);
     table.register(TAIL);
     table.register(PRETTYJSON);
+    table.register(COALESCE);
+    table.register(TYPE);
 
     #[cfg(feature = "encoding-functions")]
     table.register(URLDECODE);
@@ -205,6 +388,117 @@ mod test_builtin_table {
         assert_eq!(true, result.as_int().unwrap() > WAS_NOW);
     }
 
+    #[test]
+    fn test_time_ms() {
+        let mut state = ParserState::new();
+
+        let result = TIME_MS.call(&Token::dummy(""), &mut state, &[]).unwrap();
+        assert_eq!(true, result.as_int().unwrap() > WAS_NOW * 1000);
+    }
+
+    #[test]
+    fn test_now_ms_elapsed() {
+        let mut state = ParserState::new();
+
+        let now = state.eval("now_ms()").unwrap().as_int().unwrap();
+        let time = state.eval("time() * 1000").unwrap().as_int().unwrap();
+        assert!((now - time).abs() < 2000);
+
+        let elapsed = state
+            .eval(&format!("elapsed({})", now))
+            .unwrap()
+            .as_int()
+            .unwrap();
+        assert!((0..2000).contains(&elapsed));
+    }
+
+    #[test]
+    fn test_weekday_day_of_year() {
+        let mut state = ParserState::new();
+
+        assert_eq!(
+            Value::from("Thursday"),
+            WEEKDAY
+                .call(&Token::dummy(""), &mut state, &[Value::Integer(0)])
+                .unwrap()
+        );
+        assert_eq!(
+            Value::from("Tuesday"),
+            WEEKDAY
+                .call(&Token::dummy(""), &mut state, &[Value::Integer(1700000000)])
+                .unwrap()
+        );
+
+        assert_eq!(
+            Value::Integer(1),
+            DAY_OF_YEAR
+                .call(&Token::dummy(""), &mut state, &[Value::Integer(0)])
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Integer(318),
+            DAY_OF_YEAR
+                .call(&Token::dummy(""), &mut state, &[Value::Integer(1700000000)])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_add_days_date_diff() {
+        let mut state = ParserState::new();
+
+        // 2023-01-30 00:00:00 + 3 days = 2023-02-02 00:00:00
+        assert_eq!(
+            Value::Integer(1675296000),
+            ADD_DAYS
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::Integer(1675036800), Value::Integer(3)]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Integer(1675036800),
+            ADD_DAYS
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::Integer(1675296000), Value::Integer(-3)]
+                )
+                .unwrap()
+        );
+
+        assert_eq!(
+            Value::Integer(3),
+            DATE_DIFF
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[
+                        Value::Integer(1675036800),
+                        Value::Integer(1675296000),
+                        Value::from("days")
+                    ]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Integer(-72),
+            DATE_DIFF
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[
+                        Value::Integer(1675296000),
+                        Value::Integer(1675036800),
+                        Value::from("hours")
+                    ]
+                )
+                .unwrap()
+        );
+    }
+
     #[test]
     fn test_tail() {
         let mut state = ParserState::new();
@@ -235,6 +529,73 @@ mod test_builtin_table {
         assert_eq!("{\n  \"test\": [\n    1,\n    2,\n    3,\n    [\n      1,\n      {\n        \"2\": 3\n      }\n    ]\n  ]\n}", result.as_string());
     }
 
+    #[test]
+    fn test_coalesce() {
+        let mut state = ParserState::new();
+
+        assert_eq!(
+            Value::Integer(5),
+            COALESCE
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::from(""), Value::Integer(5), Value::Integer(6)]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            Value::from("b"),
+            COALESCE
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::None, Value::None, Value::from("b")]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            Value::None,
+            COALESCE
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::None, Value::from("")]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Integer(0),
+            COALESCE
+                .call(&Token::dummy(""), &mut state, &[Value::Integer(0)])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_type() {
+        let mut state = ParserState::new();
+
+        for (value, expected) in [
+            (Value::None, "none"),
+            (Value::Boolean(true), "boolean"),
+            (Value::Integer(5), "integer"),
+            (Value::Float(5.0), "float"),
+            (Value::from("5"), "string"),
+            (Value::Array(vec![Value::Integer(5)]), "array"),
+            (Value::Object(crate::value::ObjectType::new()), "object"),
+        ] {
+            assert_eq!(
+                Value::from(expected),
+                TYPE.call(&Token::dummy(""), &mut state, &[value]).unwrap()
+            );
+        }
+
+        assert_eq!(
+            Value::from("array"),
+            Token::new("type([1, 2])", &mut state).unwrap().value()
+        );
+    }
+
     #[cfg(feature = "encoding-functions")]
     #[test]
     fn test_urlencode_decode() {
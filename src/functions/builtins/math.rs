@@ -1,7 +1,7 @@
 //! Builtin functions for advanced mathematics
 
 use super::*;
-use crate::value::{IntegerType, Value};
+use crate::value::{FloatType, IntegerType, Value};
 use crate::ExpectedTypes;
 
 const BOOL: FunctionDefinition = FunctionDefinition {
@@ -17,13 +17,90 @@ const BOOL: FunctionDefinition = FunctionDefinition {
 const ARRAY: FunctionDefinition = FunctionDefinition {
     name: "array",
     category: Some("math"),
-    description: "Returns a value as an array",
+    description: "Returns a value as an array. Arrays are unchanged, and objects become an array of their values",
     arguments: || vec![FunctionArgument::new_required("n", ExpectedTypes::Any)],
     handler: |_function, _token, _state, args| {
         Ok(Value::Array(args.get("n").required().as_array()))
     },
 };
 
+const COERCE_ARRAY: FunctionDefinition = FunctionDefinition {
+    name: "coerce_array",
+    category: Some("math"),
+    description: "Returns arrays unchanged, and wraps any other value in an array",
+    arguments: || vec![FunctionArgument::new_required("n", ExpectedTypes::Any)],
+    handler: |_function, _token, _state, args| {
+        let n = args.get("n").required();
+        if n.is_array() {
+            Ok(n)
+        } else {
+            Ok(Value::Array(vec![n]))
+        }
+    },
+};
+
+#[cfg(feature = "bigint")]
+const BIGINT: FunctionDefinition = FunctionDefinition {
+    name: "bigint",
+    category: Some("math"),
+    description: "Returns an integer or string of digits as an arbitrary-precision integer",
+    arguments: || vec![FunctionArgument::new_required("n", ExpectedTypes::Any)],
+    handler: |_function, token, _state, args| {
+        let n = args.get("n").required();
+        if let Some(v) = n.as_bigint() {
+            return Ok(Value::BigInt(v));
+        }
+
+        match n.as_string().trim().replace(',', "").parse() {
+            Ok(v) => Ok(Value::BigInt(v)),
+            Err(_) => Err(Error::ValueParsing {
+                input: n.as_string(),
+                expected_type: ExpectedTypes::Int,
+                token: token.clone(),
+            }),
+        }
+    },
+};
+
+#[cfg(feature = "bytes")]
+const TO_BYTES: FunctionDefinition = FunctionDefinition {
+    name: "to_bytes",
+    category: Some("math"),
+    description:
+        "Returns a string as its UTF-8 bytes, or an array of integers from 0 to 255 as raw bytes",
+    arguments: || vec![FunctionArgument::new_required("s", ExpectedTypes::Any)],
+    handler: |_function, token, _state, args| {
+        let s = args.get("s").required();
+        match s.as_bytes() {
+            Some(b) => Ok(Value::Bytes(b)),
+            None => Err(Error::ValueType {
+                value: s,
+                expected_type: ExpectedTypes::String,
+                token: token.clone(),
+            }),
+        }
+    },
+};
+
+#[cfg(feature = "bytes")]
+const BYTES_LEN: FunctionDefinition = FunctionDefinition {
+    name: "bytes_len",
+    category: Some("math"),
+    description: "Returns the number of bytes in a value, counting strings by their UTF-8 encoding",
+    arguments: || vec![FunctionArgument::new_required("b", ExpectedTypes::Any)],
+    handler: |_function, token, _state, args| {
+        let b = args.get("b").required();
+        match b.as_bytes() {
+            Some(b) => Ok(Value::Integer(b.len() as IntegerType)),
+            None => Err(Error::ValueType {
+                value: b,
+                expected_type: ExpectedTypes::String,
+                token: token.clone(),
+            }),
+        }
+    },
+};
+
 const INT: FunctionDefinition = FunctionDefinition {
     name: "int",
     category: Some("math"),
@@ -34,8 +111,16 @@ const INT: FunctionDefinition = FunctionDefinition {
             ExpectedTypes::IntOrFloat,
         )]
     },
-    handler: |_function, _token, _state, args| {
-        Ok(Value::Integer(args.get("n").required().as_int().unwrap()))
+    handler: |_function, token, _state, args| {
+        let n = args.get("n").required();
+        match n.as_int() {
+            Some(i) => Ok(Value::Integer(i)),
+            None => Err(Error::ValueType {
+                value: n,
+                expected_type: ExpectedTypes::IntOrFloat,
+                token: token.clone(),
+            }),
+        }
     },
 };
 
@@ -49,24 +134,172 @@ const FLOAT: FunctionDefinition = FunctionDefinition {
             ExpectedTypes::IntOrFloat,
         )]
     },
-    handler: |_function, _token, _state, args| {
-        Ok(Value::Float(args.get("n").required().as_float().unwrap()))
+    handler: |_function, token, _state, args| {
+        let n = args.get("n").required();
+        match n.as_float() {
+            Some(f) => Ok(Value::Float(f)),
+            None => Err(Error::ValueType {
+                value: n,
+                expected_type: ExpectedTypes::IntOrFloat,
+                token: token.clone(),
+            }),
+        }
     },
 };
 
-const MIN: FunctionDefinition = FunctionDefinition {
-    name: "min",
+const TO_INT: FunctionDefinition = FunctionDefinition {
+    name: "to_int",
+    category: Some("math"),
+    description: "Parses a value as an integer, failing if it is not a valid number",
+    arguments: || vec![FunctionArgument::new_required("n", ExpectedTypes::Any)],
+    handler: |_function, token, _state, args| {
+        let n = args.get("n").required();
+        if let Some(i) = n.as_int() {
+            return Ok(Value::Integer(i));
+        }
+
+        let input = n.as_string();
+        match input.trim().replace(',', "").parse::<IntegerType>() {
+            Ok(i) => Ok(Value::Integer(i)),
+            Err(_) => Err(Error::ValueParsing {
+                input,
+                expected_type: ExpectedTypes::Int,
+                token: token.clone(),
+            }),
+        }
+    },
+};
+
+const TO_FLOAT: FunctionDefinition = FunctionDefinition {
+    name: "to_float",
     category: Some("math"),
-    description: "Returns the smallest numeric value from the supplied arguments",
+    description: "Parses a value as a float, failing if it is not a valid number",
+    arguments: || vec![FunctionArgument::new_required("n", ExpectedTypes::Any)],
+    handler: |_function, token, _state, args| {
+        let n = args.get("n").required();
+        if let Some(f) = n.as_float() {
+            return Ok(Value::Float(f));
+        }
+
+        let input = n.as_string();
+        match input.trim().replace(',', "").parse::<FloatType>() {
+            Ok(f) => Ok(Value::Float(f)),
+            Err(_) => Err(Error::ValueParsing {
+                input,
+                expected_type: ExpectedTypes::Float,
+                token: token.clone(),
+            }),
+        }
+    },
+};
+
+const PARSE_NUMBER: FunctionDefinition = FunctionDefinition {
+    name: "parse_number",
+    category: Some("math"),
+    description: "Parses a string as a number, detecting 0x, 0o and 0b base prefixes",
     arguments: || {
-        vec![FunctionArgument::new_plural(
-            "n",
-            ExpectedTypes::IntOrFloat,
-            false,
+        vec![FunctionArgument::new_required(
+            "input",
+            ExpectedTypes::String,
         )]
     },
-    handler: |_function, _token, _state, args| {
-        let mut valid_args = args
+    handler: |_function, token, _state, args| {
+        let input = args.get("input").required().as_string();
+        let trimmed = input.trim().replace(',', "");
+        let (negative, digits) = match trimmed.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, trimmed.as_str()),
+        };
+
+        let error = || Error::ValueParsing {
+            input: input.clone(),
+            expected_type: ExpectedTypes::IntOrFloat,
+            token: token.clone(),
+        };
+
+        let radix = match digits.get(0..2).map(|p| p.to_lowercase()).as_deref() {
+            Some("0x") => Some(16),
+            Some("0o") => Some(8),
+            Some("0b") => Some(2),
+            _ => None,
+        };
+
+        let value = match radix {
+            // from_str_radix and parse would otherwise accept a second sign
+            Some(_) if digits[2..].starts_with(['+', '-']) => return Err(error()),
+            None if digits.starts_with(['+', '-']) => return Err(error()),
+            Some(base) => {
+                let n =
+                    crate::handlers::parse_radix(&digits[2..], &[], base).map_err(|_| error())?;
+                Value::Integer(n)
+            }
+            None => {
+                if let Ok(n) = digits.parse::<IntegerType>() {
+                    Value::Integer(n)
+                } else {
+                    match digits.parse::<FloatType>() {
+                        Ok(n) if n.is_finite() => Value::Float(n),
+                        _ => return Err(error()),
+                    }
+                }
+            }
+        };
+
+        if negative {
+            Ok(match value {
+                Value::Integer(n) => Value::Integer(-n),
+                Value::Float(n) => Value::Float(-n),
+                v => v,
+            })
+        } else {
+            Ok(value)
+        }
+    },
+};
+
+/// Collect the values compared by min and max
+/// A single array argument is expanded into its elements, and non-numeric values are rejected
+///
+/// # Arguments
+/// * `function` - Function being called
+/// * `token` - Token at which the function was called
+/// * `args` - Function arguments
+fn numeric_arguments(
+    function: &FunctionDefinition,
+    token: &Token,
+    args: &FunctionArgumentCollection,
+) -> Result<Vec<Value>, Error> {
+    let values = match args.get("n").plural().as_slice() {
+        [Value::Array(elements)] if elements.is_empty() => {
+            return Err(Error::ArrayEmpty(token.clone()))
+        }
+        [Value::Array(elements)] => elements.clone(),
+        values => values.to_vec(),
+    };
+
+    for (i, value) in values.iter().enumerate() {
+        if !value.is_numeric() {
+            return Err(Error::FunctionArgumentType {
+                arg: i + 1,
+                expected_type: ExpectedTypes::IntOrFloat,
+                signature: function.signature(),
+                token: token.clone(),
+            });
+        }
+    }
+
+    Ok(values)
+}
+
+const MIN: FunctionDefinition = FunctionDefinition {
+    name: "min",
+    category: Some("math"),
+    description:
+        "Returns the smallest numeric value from the supplied arguments, or from a single array",
+    arguments: || vec![FunctionArgument::new_plural("n", ExpectedTypes::Any, false)],
+    handler: |function, token, _state, args| {
+        let values = numeric_arguments(function, token, &args)?;
+        let mut valid_args = values
             .iter()
             .filter(|a| !a.as_float().unwrap().is_nan())
             .cloned()
@@ -77,10 +310,13 @@ const MIN: FunctionDefinition = FunctionDefinition {
                 .partial_cmp(&b.as_float().unwrap())
                 .unwrap()
         });
-        if valid_args.is_empty() {
-            Ok(args.get("n").plural().first().cloned().unwrap())
-        } else {
-            Ok(valid_args[0].clone())
+        match valid_args.first() {
+            Some(value) => Ok(value.clone()),
+            None => Err(Error::ValueType {
+                value: values[0].clone(),
+                expected_type: ExpectedTypes::IntOrFloat,
+                token: token.clone(),
+            }),
         }
     },
 };
@@ -88,16 +324,12 @@ const MIN: FunctionDefinition = FunctionDefinition {
 const MAX: FunctionDefinition = FunctionDefinition {
     name: "max",
     category: Some("math"),
-    description: "Returns the largest numeric value from the supplied arguments",
-    arguments: || {
-        vec![FunctionArgument::new_plural(
-            "n",
-            ExpectedTypes::IntOrFloat,
-            false,
-        )]
-    },
-    handler: |_function, _token, _state, args| {
-        let mut valid_args = args
+    description:
+        "Returns the largest numeric value from the supplied arguments, or from a single array",
+    arguments: || vec![FunctionArgument::new_plural("n", ExpectedTypes::Any, false)],
+    handler: |function, token, _state, args| {
+        let values = numeric_arguments(function, token, &args)?;
+        let mut valid_args = values
             .iter()
             .filter(|a| !a.as_float().unwrap().is_nan())
             .cloned()
@@ -108,10 +340,13 @@ const MAX: FunctionDefinition = FunctionDefinition {
                 .partial_cmp(&a.as_float().unwrap())
                 .unwrap()
         });
-        if valid_args.is_empty() {
-            Ok(args.get("n").plural().first().cloned().unwrap())
-        } else {
-            Ok(valid_args[0].clone())
+        match valid_args.first() {
+            Some(value) => Ok(value.clone()),
+            None => Err(Error::ValueType {
+                value: values[0].clone(),
+                expected_type: ExpectedTypes::IntOrFloat,
+                token: token.clone(),
+            }),
         }
     },
 };
@@ -291,13 +526,145 @@ const ROOT: FunctionDefinition = FunctionDefinition {
     },
 };
 
+/// Number of ways to choose r of n items, ignoring order
+/// Multiplies and divides incrementally instead of computing the full factorials
+///
+/// # Arguments
+/// * `n` - Number of items
+/// * `r` - Number of items chosen
+fn combinations(n: IntegerType, r: IntegerType) -> Option<IntegerType> {
+    if r > n {
+        return Some(0);
+    }
+
+    // Each intermediate result is itself a binomial coefficient no larger than the final one
+    let r = r.min(n - r);
+    let mut acc: i128 = 1;
+    for i in 0..r {
+        acc = acc * (n - i) as i128 / (i + 1) as i128;
+        IntegerType::try_from(acc).ok()?;
+    }
+    IntegerType::try_from(acc).ok()
+}
+
+/// Number of ways to arrange r of n items, where order matters
+///
+/// # Arguments
+/// * `n` - Number of items
+/// * `r` - Number of items chosen
+fn permutations(n: IntegerType, r: IntegerType) -> Option<IntegerType> {
+    if r > n {
+        return Some(0);
+    }
+
+    (n - r + 1..=n).try_fold(1, |acc: IntegerType, i| acc.checked_mul(i))
+}
+
+/// Apply a combinatoric function to the n and r arguments
+///
+/// # Arguments
+/// * `method` - Function to apply
+/// * `token` - Source token, for errors
+/// * `args` - Function arguments
+fn builtin_combinatoric(
+    method: fn(IntegerType, IntegerType) -> Option<IntegerType>,
+    token: &Token,
+    args: FunctionArgumentCollection,
+) -> Result<Value, Error> {
+    let n = args.get("n").required().as_int().unwrap();
+    let r = args.get("r").required().as_int().unwrap();
+    if n < 0 || r < 0 {
+        return Err(Error::Underflow(token.clone()));
+    }
+
+    match method(n, r) {
+        Some(result) => Ok(Value::Integer(result)),
+        None => Err(Error::Overflow(token.clone())),
+    }
+}
+
+const FACTORIAL: FunctionDefinition = FunctionDefinition {
+    name: "factorial",
+    category: Some("math"),
+    description: "Returns the factorial of n, like the ! operator",
+    arguments: || vec![FunctionArgument::new_required("n", ExpectedTypes::Any)],
+    handler: |_function, token, _state, args| {
+        crate::handlers::factorial(token, &args.get("n").required())
+    },
+};
+
+const FIB: FunctionDefinition = FunctionDefinition {
+    name: "fib",
+    category: Some("math"),
+    description: "Returns the nth number in the fibonacci sequence",
+    arguments: || vec![FunctionArgument::new_required("n", ExpectedTypes::Int)],
+    handler: |_function, token, _state, args| {
+        let input = args.get("n").required();
+        let n = input.as_int().unwrap();
+        if n < 0 {
+            return Err(Error::ValueType {
+                value: input,
+                expected_type: ExpectedTypes::Int,
+                token: token.clone(),
+            });
+        }
+
+        // Starting from fib(-1) and fib(0) means no term past fib(n) is ever computed
+        let (mut a, mut b): (IntegerType, IntegerType) = (1, 0);
+        for _ in 0..n {
+            match a.checked_add(b) {
+                Some(next) => (a, b) = (b, next),
+                None => return Err(Error::Overflow(token.clone())),
+            }
+        }
+        Ok(Value::Integer(b))
+    },
+};
+
+const NCR: FunctionDefinition = FunctionDefinition {
+    name: "ncr",
+    category: Some("math"),
+    description: "Returns the number of ways to choose r items from n, ignoring order",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("n", ExpectedTypes::Int),
+            FunctionArgument::new_required("r", ExpectedTypes::Int),
+        ]
+    },
+    handler: |_function, token, _state, args| builtin_combinatoric(combinations, token, args),
+};
+
+const NPR: FunctionDefinition = FunctionDefinition {
+    name: "npr",
+    category: Some("math"),
+    description: "Returns the number of ways to arrange r items from n, where order matters",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("n", ExpectedTypes::Int),
+            FunctionArgument::new_required("r", ExpectedTypes::Int),
+        ]
+    },
+    handler: |_function, token, _state, args| builtin_combinatoric(permutations, token, args),
+};
+
 /// Register string functions
 pub fn register_functions(table: &mut FunctionTable) {
     // Typecasting
     table.register(BOOL);
     table.register(ARRAY);
+    table.register(COERCE_ARRAY);
+
+    #[cfg(feature = "bigint")]
+    table.register(BIGINT);
+    #[cfg(feature = "bytes")]
+    table.register(TO_BYTES);
+    #[cfg(feature = "bytes")]
+    table.register(BYTES_LEN);
     table.register(INT);
     table.register(FLOAT);
+    table.register(TO_INT);
+    table.register(TO_FLOAT);
+    table.register(PARSE_NUMBER);
 
     // Rounding functions
     table.register(MIN);
@@ -313,12 +680,214 @@ pub fn register_functions(table: &mut FunctionTable) {
     table.register(LOG);
     table.register(SQRT);
     table.register(ROOT);
+
+    // Combinatorics
+    table.register(FACTORIAL);
+    table.register(FIB);
+    table.register(NCR);
+    table.register(NPR);
 }
 
 #[cfg(test)]
 mod test_builtin_functions {
     use super::*;
-    use crate::value::FloatType;
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_bytes() {
+        let mut state = ParserState::new();
+
+        assert_token_value_stateful!("to_bytes('hi')", Value::Bytes(vec![0x68, 0x69]), &mut state);
+        assert_token_value_stateful!("to_bytes([0, 255])", Value::Bytes(vec![0, 255]), &mut state);
+        assert_token_text_stateful!("to_bytes('hi')", "6869", &mut state);
+        assert_token_value_stateful!(
+            "bytes_len(to_bytes('héllo'))",
+            Value::Integer(6),
+            &mut state
+        );
+        assert_token_value_stateful!("bytes_len(to_bytes([]))", Value::Integer(0), &mut state);
+        assert_token_value_stateful!("bytes_len('abc')", Value::Integer(3), &mut state);
+
+        assert_token_error_stateful!("to_bytes([256])", ValueType, &mut state);
+        assert_token_error_stateful!("to_bytes(1.5)", ValueType, &mut state);
+        assert_token_error_stateful!("bytes_len(5)", ValueType, &mut state);
+
+        // Round trips through the parseable form
+        let b = Value::Bytes(vec![1, 2, 3]);
+        assert_token_value_stateful!(&b.to_parseable_string(), b.clone(), &mut state);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint() {
+        let mut state = ParserState::new();
+        let big = |s: &str| Value::BigInt(s.parse().unwrap());
+
+        assert_eq!(
+            big("123456789012345678901234567890"),
+            BIGINT
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::from("123,456,789,012,345,678,901,234,567,890")]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            true,
+            matches!(
+                BIGINT.call(&Token::dummy(""), &mut state, &[Value::from("12a")]),
+                Err(Error::ValueParsing { .. })
+            )
+        );
+
+        assert_token_error_stateful!(
+            "999999999999999999 * 999999999999999999",
+            Overflow,
+            &mut state
+        );
+        assert_token_value_stateful!(
+            "bigint(999999999999999999) * 999999999999999999",
+            big("999999999999999998000000000000000001"),
+            &mut state
+        );
+        assert_token_value_stateful!(
+            "bigint(9223372036854775807) + 1",
+            big("9223372036854775808"),
+            &mut state
+        );
+        assert_token_value_stateful!(
+            "bigint(2) ** 100",
+            big("1267650600228229401496703205376"),
+            &mut state
+        );
+        assert_token_value_stateful!("bigint(-7) % 3", big("2"), &mut state);
+        assert_token_error_stateful!("bigint(1) / 0", DivideByZero, &mut state);
+    }
+
+    #[test]
+    fn test_coerce_array() {
+        let mut state = ParserState::new();
+        let array = Value::Array(vec![Value::Integer(1), Value::Integer(2)]);
+
+        assert_eq!(
+            Value::Array(vec![Value::Integer(5)]),
+            COERCE_ARRAY
+                .call(&Token::dummy(""), &mut state, &[Value::Integer(5)])
+                .unwrap()
+        );
+        assert_eq!(
+            array,
+            COERCE_ARRAY
+                .call(&Token::dummy(""), &mut state, &[array.clone()])
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Array(vec![array.clone()]),
+            COERCE_ARRAY
+                .call(&Token::dummy(""), &mut state, &[Value::Array(vec![array])])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_int_float_invalid_input() {
+        let mut state = ParserState::new();
+        let token = Token::dummy("");
+
+        // Bypass argument validation to reach the handlers directly
+        let mut args = FunctionArgumentCollection::new();
+        args.add("n".to_string(), Value::from("abc"));
+        assert_eq!(
+            true,
+            matches!(
+                (INT.handler)(&INT, &token, &mut state, args),
+                Err(Error::ValueType { .. })
+            )
+        );
+
+        let mut args = FunctionArgumentCollection::new();
+        args.add("n".to_string(), Value::from("abc"));
+        assert_eq!(
+            true,
+            matches!(
+                (FLOAT.handler)(&FLOAT, &token, &mut state, args),
+                Err(Error::ValueType { .. })
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_int() {
+        let mut state = ParserState::new();
+
+        assert_eq!(
+            Value::Integer(42),
+            TO_INT
+                .call(&Token::dummy(""), &mut state, &[Value::from("42")])
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Integer(3),
+            TO_INT
+                .call(&Token::dummy(""), &mut state, &[Value::Float(3.5)])
+                .unwrap()
+        );
+        assert_eq!(
+            true,
+            matches!(
+                TO_INT.call(&Token::dummy(""), &mut state, &[Value::from("abc")]),
+                Err(Error::ValueParsing { .. })
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_float() {
+        let mut state = ParserState::new();
+
+        assert_eq!(
+            Value::Float(2.5),
+            TO_FLOAT
+                .call(&Token::dummy(""), &mut state, &[Value::from("2.5")])
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Float(4.0),
+            TO_FLOAT
+                .call(&Token::dummy(""), &mut state, &[Value::Integer(4)])
+                .unwrap()
+        );
+        assert_eq!(
+            true,
+            matches!(
+                TO_FLOAT.call(&Token::dummy(""), &mut state, &[Value::from("abc")]),
+                Err(Error::ValueParsing { .. })
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_number() {
+        let mut state = ParserState::new();
+        let mut call =
+            |input: &str| PARSE_NUMBER.call(&Token::dummy(""), &mut state, &[Value::from(input)]);
+
+        assert_eq!(Value::Integer(255), call("0xFF").unwrap());
+        assert_eq!(Value::Integer(255), call("0XfF").unwrap());
+        assert_eq!(Value::Integer(5), call("0b101").unwrap());
+        assert_eq!(Value::Integer(63), call("0o77").unwrap());
+        assert_eq!(Value::Integer(1000), call(" 1,000 ").unwrap());
+        assert_eq!(Value::Integer(-16), call("-0x10").unwrap());
+        assert_eq!(Value::Float(12.5), call("12.5").unwrap());
+        assert_eq!(Value::Float(2.5e-3), call("2.5e-3").unwrap());
+
+        for input in [
+            "abc", "0x", "0xZZ", "0b102", "0x-1", "", "inf", "--5", "-+5", "-+1.5",
+        ] {
+            assert!(matches!(call(input), Err(Error::ValueParsing { .. })));
+        }
+    }
 
     #[test]
     fn test_min() {
@@ -388,6 +957,105 @@ mod test_builtin_functions {
         );
     }
 
+    #[test]
+    fn test_min_max_array() {
+        let mut state = ParserState::new();
+
+        assert_token_value_stateful!("max(1, 2, 3)", Value::Integer(3), &mut state);
+        assert_token_value_stateful!("max([1, 2, 3])", Value::Integer(3), &mut state);
+        assert_token_value_stateful!("min([4, 2.5, 9])", Value::Float(2.5), &mut state);
+        assert_token_value_stateful!("min([7])", Value::Integer(7), &mut state);
+        assert_token_error_stateful!("max([])", ArrayEmpty, &mut state);
+        assert_token_error_stateful!("min([1, 2], 3)", FunctionArgumentType, &mut state);
+    }
+
+    #[test]
+    fn test_min_max_invalid() {
+        let mut state = ParserState::new();
+        let nan = [Value::Float(FloatType::NAN), Value::Float(FloatType::NAN)];
+
+        assert!(matches!(
+            MIN.call(&Token::dummy(""), &mut state, &nan),
+            Err(Error::ValueType { .. })
+        ));
+        assert!(matches!(
+            MAX.call(&Token::dummy(""), &mut state, &nan),
+            Err(Error::ValueType { .. })
+        ));
+        assert!(matches!(
+            MAX.call(
+                &Token::dummy(""),
+                &mut state,
+                &[Value::Integer(1), Value::from("2")]
+            ),
+            Err(Error::FunctionArgumentType { arg: 2, .. })
+        ));
+        assert!(matches!(
+            MIN.call(
+                &Token::dummy(""),
+                &mut state,
+                &[Value::Array(vec![Value::Integer(1), Value::from("a")])]
+            ),
+            Err(Error::FunctionArgumentType { .. })
+        ));
+    }
+
+    #[test]
+    fn test_factorial() {
+        let mut state = ParserState::new();
+
+        assert_token_value_stateful!("factorial(0)", Value::Integer(1), &mut state);
+        assert_token_value_stateful!("factorial(4)", Value::Integer(24), &mut state);
+        assert_token_value_stateful!("factorial(len([1, 2, 3]))", Value::Integer(6), &mut state);
+        assert_token_value_stateful!(
+            "factorial([0, 2, 4])",
+            Value::Array(vec![
+                Value::Integer(1),
+                Value::Integer(2),
+                Value::Integer(24)
+            ]),
+            &mut state
+        );
+        assert_eq!(
+            state.eval("factorial(20)").unwrap(),
+            state.eval("20!").unwrap()
+        );
+        assert_token_error_stateful!("factorial(21)", Overflow, &mut state);
+        assert_token_error_stateful!("factorial(-1)", Underflow, &mut state);
+        assert_token_error_stateful!("factorial('a')", ValueType, &mut state);
+    }
+
+    #[test]
+    fn test_fib() {
+        let mut state = ParserState::new();
+
+        assert_token_value_stateful!("fib(0)", Value::Integer(0), &mut state);
+        assert_token_value_stateful!("fib(1)", Value::Integer(1), &mut state);
+        assert_token_value_stateful!("fib(10)", Value::Integer(55), &mut state);
+        assert_token_value_stateful!("fib(92)", Value::Integer(7540113804746346429), &mut state);
+        assert_token_error_stateful!("fib(93)", Overflow, &mut state);
+        assert_token_error_stateful!("fib(-1)", ValueType, &mut state);
+    }
+
+    #[test]
+    fn test_ncr_npr() {
+        let mut state = ParserState::new();
+
+        assert_token_value_stateful!("ncr(5, 2)", Value::Integer(10), &mut state);
+        assert_token_value_stateful!("npr(5, 2)", Value::Integer(20), &mut state);
+        assert_token_value_stateful!("ncr(5, 0)", Value::Integer(1), &mut state);
+        assert_token_value_stateful!("ncr(2, 5)", Value::Integer(0), &mut state);
+        assert_token_value_stateful!("npr(2, 5)", Value::Integer(0), &mut state);
+        assert_token_value_stateful!(
+            "ncr(66, 33)",
+            Value::Integer(7219428434016265740),
+            &mut state
+        );
+        assert_token_error_stateful!("ncr(68, 34)", Overflow, &mut state);
+        assert_token_error_stateful!("npr(30, 20)", Overflow, &mut state);
+        assert_token_error_stateful!("ncr(-5, 2)", Underflow, &mut state);
+    }
+
     #[test]
     fn test_ceil() {
         let mut state = ParserState::new();
@@ -23,6 +23,32 @@ const RESOLVE: FunctionDefinition = FunctionDefinition {
     },
 };
 
+#[cfg(feature = "dns-functions")]
+const PTR: FunctionDefinition = FunctionDefinition {
+    name: "ptr",
+    category: Some("network"),
+    description:
+        "Returns the hostname associated to a given IP address, or none if there is no PTR record",
+    arguments: || vec![FunctionArgument::new_required("ip", ExpectedTypes::String)],
+    handler: |_function, token, _state, args| {
+        let address = match args.get("ip").required().as_string().parse() {
+            Ok(address) => address,
+            Err(_) => {
+                return Err(Error::StringFormat {
+                    expected_format: "IP address".to_string(),
+                    token: token.clone(),
+                })
+            }
+        };
+
+        match reverse_resolve(&address) {
+            Ok(Some(hostname)) => Ok(Value::String(hostname)),
+            Ok(None) => Ok(Value::None),
+            Err(e) => Err(Error::Io(e, token.clone())),
+        }
+    },
+};
+
 const GET: FunctionDefinition = FunctionDefinition {
     name: "get",
     category: Some("network"),
@@ -86,6 +112,10 @@ const POST: FunctionDefinition = FunctionDefinition {
 /// Register network functions
 pub fn register_functions(table: &mut FunctionTable) {
     table.register(RESOLVE);
+
+    #[cfg(feature = "dns-functions")]
+    table.register(PTR);
+
     table.register(GET);
     table.register(POST);
 }
@@ -163,4 +193,24 @@ mod test_builtin_table {
             .as_string();
         assert_eq!(true, result == "127.0.0.1" || result == "[::1]");
     }
+
+    #[cfg(feature = "dns-functions")]
+    #[test]
+    fn test_ptr() {
+        let mut state = ParserState::new();
+
+        let result = PTR
+            .call(&Token::dummy(""), &mut state, &[Value::from("127.0.0.1")])
+            .unwrap();
+        assert!(result.is_none() || result.as_string().contains("localhost"));
+
+        assert!(matches!(
+            PTR.call(&Token::dummy(""), &mut state, &[Value::from("not an ip")]),
+            Err(Error::StringFormat { .. })
+        ));
+        assert!(matches!(
+            PTR.call(&Token::dummy(""), &mut state, &[Value::from("999.1.1.1")]),
+            Err(Error::StringFormat { .. })
+        ));
+    }
 }
@@ -0,0 +1,546 @@
+//! Builtin functions for descriptive statistics
+
+use super::*;
+use crate::value::{ArrayType, FloatType, IntegerType, ObjectType, Value};
+use crate::ExpectedTypes;
+use std::collections::HashMap;
+
+/// Collect the elements of an array as floats
+/// Fails if any element is not numeric
+///
+/// # Arguments
+/// * `token` - Source token, for errors
+/// * `input` - Array of values
+pub fn numeric_values(token: &Token, input: &Value) -> Result<Vec<FloatType>, Error> {
+    input
+        .as_array()
+        .iter()
+        .map(|e| {
+            if e.is_numeric() {
+                Ok(e.as_float().unwrap())
+            } else {
+                Err(Error::ValueType {
+                    value: e.clone(),
+                    expected_type: ExpectedTypes::IntOrFloat,
+                    token: token.clone(),
+                })
+            }
+        })
+        .collect()
+}
+
+/// Arithmetic mean of a set of values
+pub fn mean(values: &[FloatType]) -> FloatType {
+    values.iter().sum::<FloatType>() / values.len() as FloatType
+}
+
+/// Median of a set of values
+pub fn median(values: &[FloatType]) -> FloatType {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) / 2.0
+    } else {
+        sorted[middle]
+    }
+}
+
+/// Population variance of a set of values
+pub fn variance(values: &[FloatType]) -> FloatType {
+    let m = mean(values);
+    values.iter().map(|v| (v - m).powi(2)).sum::<FloatType>() / values.len() as FloatType
+}
+
+/// Population standard deviation of a set of values
+pub fn stddev(values: &[FloatType]) -> FloatType {
+    variance(values).sqrt()
+}
+
+/// Sample variance of a set of values, using an n-1 denominator
+pub fn sample_variance(values: &[FloatType]) -> FloatType {
+    variance(values) * values.len() as FloatType / (values.len() - 1) as FloatType
+}
+
+/// Population or sample variance of the array argument to variance() or stdev()
+///
+/// # Arguments
+/// * `token` - Source token, for errors
+/// * `args` - Function arguments
+fn variance_argument(token: &Token, args: &FunctionArgumentCollection) -> Result<FloatType, Error> {
+    let input = args.get("input").required();
+    let values = numeric_values(token, &input)?;
+    let sample = args
+        .get("sample")
+        .optional_or(Value::Boolean(false))
+        .as_bool();
+
+    if values.is_empty() {
+        Err(Error::ArrayEmpty(token.clone()))
+    } else if !sample {
+        Ok(variance(&values))
+    } else if values.len() < 2 {
+        Err(Error::ValueType {
+            value: input,
+            expected_type: ExpectedTypes::Array,
+            token: token.clone(),
+        })
+    } else {
+        Ok(sample_variance(&values))
+    }
+}
+
+const SUMMARY: FunctionDefinition = FunctionDefinition {
+    name: "summary",
+    category: Some("statistics"),
+    description: "Returns the count, min, max, mean, median and standard deviation of an array",
+    arguments: || {
+        vec![FunctionArgument::new_required(
+            "input",
+            ExpectedTypes::Array,
+        )]
+    },
+    handler: |_function, token, _state, args| {
+        let input = args.get("input").required();
+        let values = numeric_values(token, &input)?;
+        if values.is_empty() {
+            return Err(Error::ArrayEmpty(token.clone()));
+        }
+
+        let elements = input.as_array();
+        Ok(Value::Object(ObjectType::from([
+            (
+                Value::from("count"),
+                Value::Integer(values.len() as IntegerType),
+            ),
+            (Value::from("min"), elements.iter().min().cloned().unwrap()),
+            (Value::from("max"), elements.iter().max().cloned().unwrap()),
+            (Value::from("mean"), Value::Float(mean(&values))),
+            (Value::from("median"), Value::Float(median(&values))),
+            (Value::from("stddev"), Value::Float(stddev(&values))),
+        ])))
+    },
+};
+
+const CORRELATION: FunctionDefinition = FunctionDefinition {
+    name: "correlation",
+    category: Some("statistics"),
+    description: "Returns the pearson correlation coefficient of two equal-length arrays",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("a", ExpectedTypes::Array),
+            FunctionArgument::new_required("b", ExpectedTypes::Array),
+        ]
+    },
+    handler: |_function, token, _state, args| {
+        let a = numeric_values(token, &args.get("a").required())?;
+        let b = numeric_values(token, &args.get("b").required())?;
+        if a.len() != b.len() {
+            return Err(Error::ArrayLengths(token.clone()));
+        } else if a.is_empty() {
+            return Err(Error::ArrayEmpty(token.clone()));
+        }
+
+        // Correlation is undefined if either input has no variance
+        for (values, name) in [(&a, "a"), (&b, "b")] {
+            if variance(values) == 0.0 {
+                return Err(Error::Range {
+                    value: args.get(name).required(),
+                    token: token.clone(),
+                });
+            }
+        }
+
+        let (mean_a, mean_b) = (mean(&a), mean(&b));
+        let covariance = a
+            .iter()
+            .zip(b.iter())
+            .map(|(x, y)| (x - mean_a) * (y - mean_b))
+            .sum::<FloatType>()
+            / a.len() as FloatType;
+        let r = covariance / (stddev(&a) * stddev(&b));
+        Ok(Value::Float(r.clamp(-1.0, 1.0)))
+    },
+};
+
+/// Reduce a group of values with a named reduction
+/// count, sum, mean and median are handled directly; any other name is
+/// called as a builtin function, with the group's values as its arguments,
+/// or else as a user function taking the group's values as a single array
+///
+/// A group with no values counts as 0, and reduces to none otherwise
+///
+/// # Arguments
+/// * `name` - Name of the reduction
+/// * `token` - Source token, for errors
+/// * `state` - Parser state, for builtin functions
+/// * `values` - Values in the group
+fn reduce_group(
+    name: &str,
+    token: &Token,
+    state: &mut ParserState,
+    values: ArrayType,
+) -> Result<Value, Error> {
+    if values.is_empty() && name != "count" {
+        return Ok(Value::None);
+    }
+
+    match name {
+        "count" => Ok(Value::Integer(values.len() as IntegerType)),
+        "sum" if values.iter().all(|v| v.is_int()) => values
+            .iter()
+            .try_fold(0, |acc: IntegerType, v| acc.checked_add(v.as_int()?))
+            .map(Value::Integer)
+            .ok_or_else(|| Error::Overflow(token.clone())),
+        "sum" => Ok(Value::Float(
+            numeric_values(token, &Value::Array(values))?.iter().sum(),
+        )),
+        "mean" | "median" => {
+            let values = numeric_values(token, &Value::Array(values))?;
+            if values.is_empty() {
+                Err(Error::ArrayEmpty(token.clone()))
+            } else if name == "mean" {
+                Ok(Value::Float(mean(&values)))
+            } else {
+                Ok(Value::Float(median(&values)))
+            }
+        }
+        _ if state.functions.has(name) => {
+            let functions = state.functions.clone();
+            functions.call(name, token, state, &values)
+        }
+        _ => {
+            let f = match state.user_functions.get(name) {
+                Some(f) => f.clone(),
+                None => {
+                    return Err(Error::FunctionName {
+                        name: name.to_string(),
+                        token: token.clone(),
+                    })
+                }
+            };
+            if f.arguments().len() != 1 {
+                return Err(Error::FunctionArguments {
+                    min: 1,
+                    max: 1,
+                    signature: f.signature(),
+                    token: token.clone(),
+                });
+            }
+
+            let mut inner_state = state
+                .spawn_inner()
+                .ok_or_else(|| Error::StackOverflow(token.clone()))?;
+            inner_state
+                .variables
+                .insert(f.arguments()[0].clone(), Value::Array(values));
+            inner_state.eval(f.definition())
+        }
+    }
+}
+
+const AGGREGATE: FunctionDefinition = FunctionDefinition {
+    name: "aggregate",
+    category: Some("statistics"),
+    description: "Group an array of objects by a key, then reduce each group's values for another key with a named function, such as sum, count, mean, median, min, max, or a user function taking an array",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("input", ExpectedTypes::Array),
+            FunctionArgument::new_required("group_key", ExpectedTypes::Any),
+            FunctionArgument::new_required("value_key", ExpectedTypes::Any),
+            FunctionArgument::new_required("function", ExpectedTypes::String),
+        ]
+    },
+    handler: |_function, token, state, args| {
+        let group_key = args.get("group_key").required();
+        let value_key = args.get("value_key").required();
+        let function = args.get("function").required().as_string();
+
+        // Records without the group key are skipped
+        let mut groups: HashMap<Value, ArrayType> = HashMap::new();
+        for record in args.get("input").required().as_array() {
+            if let Value::Object(record) = record {
+                if let Some(group) = record.get(&group_key) {
+                    let values = groups.entry(group.clone()).or_default();
+                    if let Some(value) = record.get(&value_key) {
+                        values.push(value.clone());
+                    }
+                }
+            }
+        }
+
+        let mut result = ObjectType::new();
+        for (group, values) in groups {
+            result.insert(group, reduce_group(&function, token, state, values)?);
+        }
+        Ok(Value::Object(result))
+    },
+};
+
+const VARIANCE: FunctionDefinition = FunctionDefinition {
+    name: "variance",
+    category: Some("statistics"),
+    description:
+        "Returns the variance of an array, using the sample (n-1) denominator if sample is true",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("input", ExpectedTypes::Array),
+            FunctionArgument::new_optional("sample", ExpectedTypes::Boolean),
+        ]
+    },
+    handler: |_function, token, _state, args| Ok(Value::Float(variance_argument(token, &args)?)),
+};
+
+const STDEV: FunctionDefinition = FunctionDefinition {
+    name: "stdev",
+    category: Some("statistics"),
+    description: "Returns the standard deviation of an array, using the sample (n-1) denominator if sample is true",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("input", ExpectedTypes::Array),
+            FunctionArgument::new_optional("sample", ExpectedTypes::Boolean),
+        ]
+    },
+    handler: |_function, token, _state, args| {
+        Ok(Value::Float(variance_argument(token, &args)?.sqrt()))
+    },
+};
+
+/// Register statistics functions
+pub fn register_functions(table: &mut FunctionTable) {
+    table.register(SUMMARY);
+    table.register(CORRELATION);
+    table.register(AGGREGATE);
+    table.register(VARIANCE);
+    table.register(STDEV);
+}
+
+#[cfg(test)]
+mod test_builtin_functions {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let mut state = ParserState::new();
+
+        let summary = SUMMARY
+            .call(
+                &Token::dummy(""),
+                &mut state,
+                &[Value::Array(vec![
+                    Value::Integer(2),
+                    Value::Integer(4),
+                    Value::Integer(4),
+                    Value::Integer(4),
+                    Value::Integer(5),
+                    Value::Integer(5),
+                    Value::Integer(7),
+                    Value::Integer(9),
+                ])],
+            )
+            .unwrap()
+            .as_object();
+        assert_eq!(Value::Integer(8), summary[&Value::from("count")]);
+        assert_eq!(Value::Integer(2), summary[&Value::from("min")]);
+        assert_eq!(Value::Integer(9), summary[&Value::from("max")]);
+        assert_eq!(Value::Float(5.0), summary[&Value::from("mean")]);
+        assert_eq!(Value::Float(4.5), summary[&Value::from("median")]);
+        assert_eq!(Value::Float(2.0), summary[&Value::from("stddev")]);
+
+        assert_eq!(
+            true,
+            matches!(
+                SUMMARY.call(&Token::dummy(""), &mut state, &[Value::Array(vec![])]),
+                Err(Error::ArrayEmpty(_))
+            )
+        );
+    }
+
+    #[test]
+    fn test_variance_stdev() {
+        let mut state = ParserState::new();
+        let data = "[2, 4, 4, 4, 5, 5, 7, 9]";
+
+        assert_token_value_stateful!(
+            &format!("variance({})", data),
+            Value::Float(4.0),
+            &mut state
+        );
+        assert_token_value_stateful!(
+            &format!("stdev({}, false)", data),
+            Value::Float(2.0),
+            &mut state
+        );
+
+        let sample = state
+            .eval(&format!("variance({}, true)", data))
+            .unwrap()
+            .as_float()
+            .unwrap();
+        assert!((sample - 32.0 / 7.0).abs() < 1e-9);
+        let sample = state
+            .eval(&format!("stdev({}, true)", data))
+            .unwrap()
+            .as_float()
+            .unwrap();
+        assert!((sample - 2.1380899353).abs() < 1e-9);
+
+        assert_token_value_stateful!("stdev([3])", Value::Float(0.0), &mut state);
+        assert_token_error_stateful!("variance([])", ArrayEmpty, &mut state);
+        assert_token_error_stateful!("stdev([], true)", ArrayEmpty, &mut state);
+        assert_token_error_stateful!("stdev([3], true)", ValueType, &mut state);
+        assert_token_error_stateful!("variance([1, 'a'])", ValueType, &mut state);
+    }
+
+    #[test]
+    fn test_correlation() {
+        let mut state = ParserState::new();
+        let a = Value::Array(vec![
+            Value::Integer(1),
+            Value::Integer(2),
+            Value::Integer(3),
+        ]);
+        let b = Value::Array(vec![
+            Value::Integer(2),
+            Value::Integer(4),
+            Value::Integer(6),
+        ]);
+        let c = Value::Array(vec![
+            Value::Integer(3),
+            Value::Integer(2),
+            Value::Integer(1),
+        ]);
+
+        assert_eq!(
+            Value::Float(1.0),
+            CORRELATION
+                .call(&Token::dummy(""), &mut state, &[a.clone(), b])
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Float(-1.0),
+            CORRELATION
+                .call(&Token::dummy(""), &mut state, &[a.clone(), c])
+                .unwrap()
+        );
+        assert_eq!(
+            true,
+            matches!(
+                CORRELATION.call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[a.clone(), Value::Array(vec![Value::Integer(1)])]
+                ),
+                Err(Error::ArrayLengths(_))
+            )
+        );
+        assert_eq!(
+            true,
+            matches!(
+                CORRELATION.call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[
+                        a,
+                        Value::Array(vec![
+                            Value::Integer(1),
+                            Value::Integer(1),
+                            Value::Integer(1)
+                        ])
+                    ]
+                ),
+                Err(Error::Range { .. })
+            )
+        );
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let mut state = ParserState::new();
+        let record = |category: &str, amount: IntegerType| {
+            Value::Object(ObjectType::from([
+                (Value::from("category"), Value::from(category)),
+                (Value::from("amount"), Value::Integer(amount)),
+            ]))
+        };
+        let input = Value::Array(vec![
+            record("food", 10),
+            record("rent", 500),
+            record("food", 15),
+        ]);
+
+        let call = |state: &mut ParserState, function: &str| {
+            AGGREGATE.call(
+                &Token::dummy(""),
+                state,
+                &[
+                    input.clone(),
+                    Value::from("category"),
+                    Value::from("amount"),
+                    Value::from(function),
+                ],
+            )
+        };
+
+        assert_eq!(
+            Value::Object(ObjectType::from([
+                (Value::from("food"), Value::Integer(25)),
+                (Value::from("rent"), Value::Integer(500)),
+            ])),
+            call(&mut state, "sum").unwrap()
+        );
+        assert_eq!(
+            Value::Object(ObjectType::from([
+                (Value::from("food"), Value::Integer(15)),
+                (Value::from("rent"), Value::Integer(500)),
+            ])),
+            call(&mut state, "max").unwrap()
+        );
+        assert!(matches!(
+            call(&mut state, "not_a_function"),
+            Err(Error::FunctionName { .. })
+        ));
+
+        // User functions receive the group's values as an array
+        state.eval("spread(a) = max(a) - min(a)").unwrap();
+        assert_eq!(
+            Value::Object(ObjectType::from([
+                (Value::from("food"), Value::Integer(5)),
+                (Value::from("rent"), Value::Integer(0)),
+            ])),
+            call(&mut state, "spread").unwrap()
+        );
+        state.eval("both(a, b) = a + b").unwrap();
+        assert!(matches!(
+            call(&mut state, "both"),
+            Err(Error::FunctionArguments { .. })
+        ));
+
+        // Groups without any values
+        let input = Value::Array(vec![Value::Object(ObjectType::from([(
+            Value::from("category"),
+            Value::from("food"),
+        )]))]);
+        let call = |state: &mut ParserState, function: &str| {
+            AGGREGATE.call(
+                &Token::dummy(""),
+                state,
+                &[
+                    input.clone(),
+                    Value::from("category"),
+                    Value::from("amount"),
+                    Value::from(function),
+                ],
+            )
+        };
+        assert_eq!(
+            Value::Object(ObjectType::from([(Value::from("food"), Value::Integer(0))])),
+            call(&mut state, "count").unwrap()
+        );
+        assert_eq!(
+            Value::Object(ObjectType::from([(Value::from("food"), Value::None)])),
+            call(&mut state, "max").unwrap()
+        );
+    }
+}
@@ -1,7 +1,7 @@
 //! Builtin functions for string manipulation
 
 use super::*;
-use crate::value::{IntegerType, Value};
+use crate::value::{IntegerType, ObjectType, Value};
 use crate::ExpectedTypes;
 use regex::Regex;
 
@@ -36,10 +36,10 @@ const CONCAT: FunctionDefinition = FunctionDefinition {
             true,
         )]
     },
-    handler: |_function, _token, _state, args| {
-        Ok(Value::String(
-            args.iter().map(|v| v.as_string()).collect::<String>(),
-        ))
+    handler: |_function, token, state, args| {
+        let strings = args.iter().map(|v| v.as_string()).collect::<Vec<String>>();
+        state.check_string_length(strings.iter().map(|s| s.len()).sum(), token)?;
+        Ok(Value::String(strings.concat()))
     },
 };
 
@@ -87,6 +87,287 @@ const TRIM: FunctionDefinition = FunctionDefinition {
     },
 };
 
+const SLUGIFY: FunctionDefinition = FunctionDefinition {
+    name: "slugify",
+    category: Some("strings"),
+    description: "Converts the string s to a lowercase identifier, with runs of other characters replaced by single hyphens",
+    arguments: || vec![FunctionArgument::new_required("s", ExpectedTypes::String)],
+    handler: |_function, _token, _state, args| {
+        let s = args.get("s").required().as_string().to_lowercase();
+        Ok(Value::String(
+            s.split(|c: char| !c.is_alphanumeric())
+                .filter(|w| !w.is_empty())
+                .collect::<Vec<&str>>()
+                .join("-"),
+        ))
+    },
+};
+
+/// Rotate ASCII letters by 13 places, leaving other characters untouched
+pub fn rot13(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'a'..='m' | 'A'..='M' => (c as u8 + 13) as char,
+            'n'..='z' | 'N'..='Z' => (c as u8 - 13) as char,
+            _ => c,
+        })
+        .collect()
+}
+
+const ROT13: FunctionDefinition = FunctionDefinition {
+    name: "rot13",
+    category: Some("strings"),
+    description: "Rotates the letters in string s by 13 places",
+    arguments: || vec![FunctionArgument::new_required("s", ExpectedTypes::String)],
+    handler: |_function, _token, _state, args| {
+        let s = args.get("s").required().as_string();
+        Ok(Value::String(rot13(&s)))
+    },
+};
+
+/// Percent-encode every byte of a string other than unreserved URL characters
+pub fn url_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Decode percent-encoded sequences in a string
+/// Returns None for malformed sequences, or if the result is not valid UTF-8
+pub fn url_decode(s: &str) -> Option<String> {
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut input = s.bytes();
+    while let Some(b) = input.next() {
+        if b == b'%' {
+            let hex = match [input.next(), input.next()] {
+                [Some(h), Some(l)] if h.is_ascii_hexdigit() && l.is_ascii_hexdigit() => {
+                    String::from_utf8(vec![h, l]).ok()?
+                }
+                _ => return None,
+            };
+            bytes.push(u8::from_str_radix(&hex, 16).ok()?);
+        } else {
+            bytes.push(b);
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+const URL_ENCODE: FunctionDefinition = FunctionDefinition {
+    name: "url_encode",
+    category: Some("strings"),
+    description: "Percent-encodes reserved characters in string s for use in a URL",
+    arguments: || vec![FunctionArgument::new_required("s", ExpectedTypes::String)],
+    handler: |_function, _token, _state, args| {
+        let s = args.get("s").required().as_string();
+        Ok(Value::String(url_encode(&s)))
+    },
+};
+
+const URL_DECODE: FunctionDefinition = FunctionDefinition {
+    name: "url_decode",
+    category: Some("strings"),
+    description: "Decodes percent-encoded characters in string s",
+    arguments: || vec![FunctionArgument::new_required("s", ExpectedTypes::String)],
+    handler: |_function, token, _state, args| {
+        let s = args.get("s").required().as_string();
+        match url_decode(&s) {
+            Some(s) => Ok(Value::String(s)),
+            None => Err(Error::StringFormat {
+                expected_format: "url".to_string(),
+                token: token.clone(),
+            }),
+        }
+    },
+};
+
+const PARSE_QUERY_STRING: FunctionDefinition = FunctionDefinition {
+    name: "parse_query_string",
+    category: Some("strings"),
+    description:
+        "Parses a URL query string such as a=1&b=2 into an object. Repeated keys become arrays",
+    arguments: || vec![FunctionArgument::new_required("s", ExpectedTypes::String)],
+    handler: |_function, token, _state, args| {
+        let s = args.get("s").required().as_string();
+        let decode = |component: &str| match url_decode(&component.replace('+', " ")) {
+            Some(s) => Ok(Value::String(s)),
+            None => Err(Error::StringFormat {
+                expected_format: "query string".to_string(),
+                token: token.clone(),
+            }),
+        };
+
+        let mut result = ObjectType::new();
+        for pair in s
+            .trim_start_matches('?')
+            .split('&')
+            .filter(|p| !p.is_empty())
+        {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let (key, value) = (decode(key)?, decode(value)?);
+            match result.get_mut(&key) {
+                Some(Value::Array(values)) => values.push(value),
+                Some(existing) => *existing = Value::Array(vec![existing.clone(), value]),
+                None => {
+                    result.insert(key, value);
+                }
+            }
+        }
+
+        Ok(Value::Object(result))
+    },
+};
+
+const TO_HEX: FunctionDefinition = FunctionDefinition {
+    name: "to_hex",
+    category: Some("strings"),
+    description: "Returns the hexadecimal representation of the bytes in string s",
+    arguments: || vec![FunctionArgument::new_required("s", ExpectedTypes::String)],
+    handler: |_function, token, state, args| {
+        let s = args.get("s").required().as_string();
+        state.check_string_length(s.len() * 2, token)?;
+        Ok(Value::String(
+            s.bytes().map(|b| format!("{:02X}", b)).collect(),
+        ))
+    },
+};
+
+const FROM_HEX: FunctionDefinition = FunctionDefinition {
+    name: "from_hex",
+    category: Some("strings"),
+    description: "Decodes a string of hexadecimal bytes, such as 4142, into a string",
+    arguments: || vec![FunctionArgument::new_required("s", ExpectedTypes::String)],
+    handler: |_function, token, _state, args| {
+        let s = args.get("s").required().as_string();
+        let error = || Error::StringFormat {
+            expected_format: "hex".to_string(),
+            token: token.clone(),
+        };
+        if s.len() % 2 != 0 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+
+        let bytes = (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| error()))
+            .collect::<Result<Vec<u8>, Error>>()?;
+        String::from_utf8(bytes)
+            .map(Value::String)
+            .map_err(|_| error())
+    },
+};
+
+const BUILD_QUERY_STRING: FunctionDefinition = FunctionDefinition {
+    name: "build_query_string",
+    category: Some("strings"),
+    description: "Encodes an object as a URL query string, with sorted keys. Array values become repeated keys",
+    arguments: || vec![FunctionArgument::new_required("input", ExpectedTypes::Object)],
+    handler: |_function, _token, _state, args| {
+        let mut pairs: Vec<(Value, Value)> =
+            args.get("input").required().as_object().into_iter().collect();
+        pairs.sort();
+
+        let mut query: Vec<String> = Vec::new();
+        for (key, value) in pairs {
+            let key = url_encode(&key.as_string());
+            let values = if value.is_array() {
+                value.as_array()
+            } else {
+                vec![value]
+            };
+            for value in values {
+                query.push(format!("{}={}", key, url_encode(&value.as_string())));
+            }
+        }
+
+        Ok(Value::String(query.join("&")))
+    },
+};
+
+const TEMPLATE: FunctionDefinition = FunctionDefinition {
+    name: "template",
+    category: Some("strings"),
+    description:
+        "Renders a registered template, replacing ${key} placeholders with values from data",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("name", ExpectedTypes::String),
+            FunctionArgument::new_required("data", ExpectedTypes::Object),
+        ]
+    },
+    handler: |_function, token, state, args| {
+        let name = args.get("name").required();
+        let data = args.get("data").required().as_object();
+        let mut remaining = match state.templates.get(&name.as_string()) {
+            Some(t) => t.as_str(),
+            None => {
+                return Err(Error::Index {
+                    key: name,
+                    token: token.clone(),
+                })
+            }
+        };
+
+        let mut output = String::new();
+        while let Some(start) = remaining.find("${") {
+            let end = match remaining[start..].find('}') {
+                Some(end) => start + end,
+                None => break,
+            };
+
+            let key = Value::from(remaining[start + 2..end].trim());
+            match data.get(&key) {
+                Some(v) => output.push_str(&format!("{}{}", &remaining[..start], v)),
+                None => {
+                    return Err(Error::Index {
+                        key,
+                        token: token.clone(),
+                    })
+                }
+            }
+            remaining = &remaining[end + 1..];
+        }
+        output.push_str(remaining);
+
+        Ok(Value::String(output))
+    },
+};
+
+const LEVENSHTEIN: FunctionDefinition = FunctionDefinition {
+    name: "levenshtein",
+    category: Some("strings"),
+    description: "Returns the number of single-character edits needed to turn string a into b",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("a", ExpectedTypes::String),
+            FunctionArgument::new_required("b", ExpectedTypes::String),
+        ]
+    },
+    handler: |_function, _token, _state, args| {
+        let a: Vec<char> = args.get("a").required().as_string().chars().collect();
+        let b: Vec<char> = args.get("b").required().as_string().chars().collect();
+
+        // Distances from the previous row of the edit matrix
+        let mut previous: Vec<usize> = (0..=b.len()).collect();
+        for (i, ca) in a.iter().enumerate() {
+            let mut current = vec![i + 1];
+            for (j, cb) in b.iter().enumerate() {
+                let substitution = previous[j] + usize::from(ca != cb);
+                current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+            }
+            previous = current;
+        }
+
+        Ok(Value::Integer(previous[b.len()] as IntegerType))
+    },
+};
+
 const SUBSTR : FunctionDefinition = FunctionDefinition {
     name: "substr",
     category: Some("strings"),
@@ -176,6 +457,16 @@ pub fn register_functions(table: &mut FunctionTable) {
     table.register(UPPERCASE);
     table.register(LOWERCASE);
     table.register(TRIM);
+    table.register(SLUGIFY);
+    table.register(ROT13);
+    table.register(URL_ENCODE);
+    table.register(URL_DECODE);
+    table.register(PARSE_QUERY_STRING);
+    table.register(BUILD_QUERY_STRING);
+    table.register(TEMPLATE);
+    table.register(LEVENSHTEIN);
+    table.register(TO_HEX);
+    table.register(FROM_HEX);
     table.register(SUBSTR);
     table.register(REGEX);
 }
@@ -184,6 +475,234 @@ pub fn register_functions(table: &mut FunctionTable) {
 mod test_builtin_functions {
     use super::*;
 
+    #[test]
+    fn test_rot13() {
+        let mut state = ParserState::new();
+
+        assert_eq!(
+            Value::from("Uryyb, Jbeyq!"),
+            ROT13
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::from("Hello, World!")]
+                )
+                .unwrap()
+        );
+        assert_eq!("Hello", rot13(&rot13("Hello")));
+    }
+
+    #[test]
+    fn test_slugify() {
+        let mut state = ParserState::new();
+
+        for (input, expected) in [
+            ("Hello, World!", "hello-world"),
+            ("too   many    spaces", "too-many-spaces"),
+            ("--Already a Slug?--", "already-a-slug"),
+            ("!!!", ""),
+        ] {
+            assert_eq!(
+                Value::from(expected),
+                SLUGIFY
+                    .call(&Token::dummy(""), &mut state, &[Value::from(input)])
+                    .unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_url_encode_decode() {
+        let mut state = ParserState::new();
+
+        let encoded = URL_ENCODE
+            .call(&Token::dummy(""), &mut state, &[Value::from("a b&c=d")])
+            .unwrap();
+        assert_eq!(Value::from("a%20b%26c%3Dd"), encoded);
+        assert_eq!(
+            Value::from("a b&c=d"),
+            URL_DECODE
+                .call(&Token::dummy(""), &mut state, &[encoded])
+                .unwrap()
+        );
+
+        for malformed in ["100%", "%2", "%zz", "%+1", "%-1"] {
+            assert_eq!(
+                true,
+                matches!(
+                    URL_DECODE.call(&Token::dummy(""), &mut state, &[Value::from(malformed)]),
+                    Err(Error::StringFormat { .. })
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_query_string() {
+        let mut state = ParserState::new();
+
+        assert_eq!(
+            Value::Object(ObjectType::from([
+                (Value::from("a"), Value::from("1")),
+                (Value::from("b"), Value::from("hello")),
+            ])),
+            PARSE_QUERY_STRING
+                .call(&Token::dummy(""), &mut state, &[Value::from("a=1&b=hello")])
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Object(ObjectType::from([(
+                Value::from("q"),
+                Value::from("fish & chips")
+            )])),
+            PARSE_QUERY_STRING
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::from("?q=fish%20%26+chips")]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Object(ObjectType::from([(
+                Value::from("x"),
+                Value::Array(vec![Value::from("1"), Value::from("2"), Value::from("3")])
+            )])),
+            PARSE_QUERY_STRING
+                .call(&Token::dummy(""), &mut state, &[Value::from("x=1&x=2&x=3")])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_to_from_hex() {
+        let mut state = ParserState::new();
+
+        let hex = TO_HEX
+            .call(&Token::dummy(""), &mut state, &[Value::from("AB")])
+            .unwrap();
+        assert_eq!(Value::from("4142"), hex);
+        assert_eq!(
+            Value::from("AB"),
+            FROM_HEX
+                .call(&Token::dummy(""), &mut state, &[hex])
+                .unwrap()
+        );
+
+        let hex = TO_HEX
+            .call(&Token::dummy(""), &mut state, &[Value::from("héllo")])
+            .unwrap();
+        assert_eq!(
+            Value::from("héllo"),
+            FROM_HEX
+                .call(&Token::dummy(""), &mut state, &[hex])
+                .unwrap()
+        );
+
+        for invalid in ["414", "zz", "FF", "+1", "-1"] {
+            assert_eq!(
+                true,
+                matches!(
+                    FROM_HEX.call(&Token::dummy(""), &mut state, &[Value::from(invalid)]),
+                    Err(Error::StringFormat { .. })
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_build_query_string() {
+        let mut state = ParserState::new();
+        let input = Value::Object(ObjectType::from([
+            (Value::from("q"), Value::from("fish & chips")),
+            (
+                Value::from("tag"),
+                Value::Array(vec![Value::from("a"), Value::from("b")]),
+            ),
+            (Value::from("a"), Value::from("1")),
+        ]));
+
+        let query = BUILD_QUERY_STRING
+            .call(&Token::dummy(""), &mut state, &[input.clone()])
+            .unwrap();
+        assert_eq!(Value::from("a=1&q=fish%20%26%20chips&tag=a&tag=b"), query);
+        assert_eq!(
+            input,
+            PARSE_QUERY_STRING
+                .call(&Token::dummy(""), &mut state, &[query])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_template() {
+        let mut state = ParserState::new();
+        state.register_template("greeting", "Hello ${name}, you are ${ age }!");
+
+        let data = Value::Object(ObjectType::from([
+            (Value::from("name"), Value::from("Bob")),
+            (Value::from("age"), Value::Integer(32)),
+        ]));
+        assert_eq!(
+            Value::from("Hello Bob, you are 32!"),
+            TEMPLATE
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::from("greeting"), data.clone()]
+                )
+                .unwrap()
+        );
+
+        assert_eq!(
+            true,
+            matches!(
+                TEMPLATE.call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::from("missing"), data]
+                ),
+                Err(Error::Index { .. })
+            )
+        );
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        let mut state = ParserState::new();
+
+        assert_eq!(
+            Value::Integer(3),
+            LEVENSHTEIN
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::from("kitten"), Value::from("sitting")]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Integer(0),
+            LEVENSHTEIN
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::from("same"), Value::from("same")]
+                )
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Integer(1),
+            LEVENSHTEIN
+                .call(
+                    &Token::dummy(""),
+                    &mut state,
+                    &[Value::from("naïve"), Value::from("naive")]
+                )
+                .unwrap()
+        );
+    }
+
     #[test]
     fn test_regex() {
         let mut state = ParserState::new();
@@ -23,6 +23,43 @@ define_function!(
     }
 );
 
+/// Build the complete help text, covering syntax, functions, decorators and variables
+fn full_help(state: &mut ParserState) -> String {
+    let mut help = Help::new();
+
+    help.add_block("Syntax Examples")
+        .add_entry(
+            "Assigns the result of '60*60*24' to a variable, and outputs the result as a float:",
+        )
+        .add_entry("    one_day = 60 * 60 * 24 @float // A comment")
+        .add_entry("Creates a function called 'factorial' taking 1 argument:")
+        .add_entry("    factorial(x) = x==0 ? 1 : (x * factorial(x - 1) )")
+        .add_entry("Creates a function called that uses arrays:")
+        .add_entry("    sum(a) = element(a, 0) + ( len(a)>1 ? sum(dequeue(a)) : 0 )")
+        .add_entry("Performs arithmetic between arrays, and scalars: ")
+        .add_entry("   [10, 12] + 2 * [1.2, 1.3]");
+
+    help.add_block("Operators")
+        .add_entry("   Bitwise: AND (0xF & 0xA), OR (0xA | 0xF), XOR (0xA ^ 0xF), NOT (~0xA), SHIFT (0xF >> 1, 0xA << 1)")
+        .add_entry("   Boolean: AND (true && false), OR (true || false), XOR (true ^^ false), CMP (1 < 2, 4 >= 5), EQ (1 == 1, 2 != 5)")
+        .add_entry("Arithmetic: Add/Sub (+, -), Mul/Div (*, /), Exponentiation (**), Modulo (%), Implied Mul ((5)(5), 5x)")
+        .add_entry("     Unary: Factorial (5!!), Negation (-1, -(1+1))");
+
+    help.add_block("Data Types")
+        .add_entry("  String: Text delimited by 'quotes' or \"double-quotes\"")
+        .add_entry(" Boolean: A truth value (true or false)")
+        .add_entry(" Integer: A whole number. Can also be base2 (0b111), base8 (0o777), or base16 (0xFF)")
+        .add_entry("   Float: A decimal number. Can also be in scientific notation(5.3e+4, 4E-2)")
+        .add_entry("Currency: A decimal number - does not apply any exhange rates ($5.00)")
+        .add_entry("   Array: A comma separated list of values in square brackets; [1, 'test']")
+        .add_entry("  Object: A comma separated list of key/value pairs in curly braces; {'test': 5}")
+        .add_entry("Variable: An identifier representing a value. Set it with x=5, then use it in an expression (5x)")
+        .add_entry(" Contant: A preset read-only variable representing a common value, such as pi, e, and tau");
+
+    help.add_std(state);
+    help.to_string()
+}
+
 const HELP: FunctionDefinition = FunctionDefinition {
     name: "help",
     category: None,
@@ -33,7 +70,7 @@ const HELP: FunctionDefinition = FunctionDefinition {
             ExpectedTypes::String,
         )]
     },
-    handler: |_function, token, state, args| {
+    handler: |_function, _token, state, args| {
         match args.get("function_name").optional() {
             Some(f) => {
                 let target = f.as_string();
@@ -55,45 +92,58 @@ const HELP: FunctionDefinition = FunctionDefinition {
                     return Ok(Value::String(f.signature()));
                 }
 
-                Err(Error::FunctionName {
-                    name: target,
-                    token: token.clone(),
-                })
-            }
+                // Function categories
+                let category = state.functions.by_category(&target.to_lowercase());
+                if !category.is_empty() {
+                    let mut help = Help::new();
+                    let block = help.add_block(&format!("{} Functions", target));
+                    for f in category {
+                        block.add_entry(&f.help());
+                    }
+                    return Ok(Value::String(help.to_string()));
+                }
 
-            None => {
-                let mut help = Help::new();
-
-                help.add_block("Syntax Examples")
-                    .add_entry("Assigns the result of '60*60*24' to a variable, and outputs the result as a float:")
-                    .add_entry("    one_day = 60 * 60 * 24 @float // A comment")
-                    .add_entry("Creates a function called 'factorial' taking 1 argument:")
-                    .add_entry("    factorial(x) = x==0 ? 1 : (x * factorial(x - 1) )")
-                    .add_entry("Creates a function called that uses arrays:")
-                    .add_entry("    sum(a) = element(a, 0) + ( len(a)>1 ? sum(dequeue(a)) : 0 )")
-                    .add_entry("Performs arithmetic between arrays, and scalars: ")
-                    .add_entry("   [10, 12] + 2 * [1.2, 1.3]");
-
-                help.add_block("Operators")
-                    .add_entry("   Bitwise: AND (0xF & 0xA), OR (0xA | 0xF), XOR (0xA ^ 0xF), NOT (~0xA), SHIFT (0xF >> 1, 0xA << 1)")
-                    .add_entry("   Boolean: AND (true && false), OR (true || false), CMP (1 < 2, 4 >= 5), EQ (1 == 1, 2 != 5)")
-                    .add_entry("Arithmetic: Add/Sub (+, -), Mul/Div (*, /), Exponentiation (**), Modulo (%), Implied Mul ((5)(5), 5x)")
-                    .add_entry("     Unary: Factorial (5!!), Negation (-1, -(1+1))");
-
-                help.add_block("Data Types")
-                    .add_entry("  String: Text delimited by 'quotes' or \"double-quotes\"")
-                    .add_entry(" Boolean: A truth value (true or false)")
-                    .add_entry(" Integer: A whole number. Can also be base2 (0b111), base8 (0o777), or base16 (0xFF)")
-                    .add_entry("   Float: A decimal number. Can also be in scientific notation(5.3e+4, 4E-2)")
-                    .add_entry("Currency: A decimal number - does not apply any exhange rates ($5.00)")
-                    .add_entry("   Array: A comma separated list of values in square brackets; [1, 'test']")
-                    .add_entry("  Object: A comma separated list of key/value pairs in curly braces; {'test': 5}")
-                    .add_entry("Variable: An identifier representing a value. Set it with x=5, then use it in an expression (5x)")
-                    .add_entry(" Contant: A preset read-only variable representing a common value, such as pi, e, and tau");
-
-                help.add_std(state);
-                Ok(Value::String(help.to_string()))
+                // Near-misses, by prefix or substring
+                let needle = target.to_lowercase();
+                let suggestions = state
+                    .functions
+                    .all()
+                    .iter()
+                    .filter(|f| f.name().contains(&needle))
+                    .map(|f| f.help())
+                    .collect::<Vec<String>>();
+                if !suggestions.is_empty() {
+                    return Ok(Value::String(format!(
+                        "Did you mean:\n{}",
+                        suggestions.join("\n")
+                    )));
+                }
+
+                Ok(Value::String(full_help(state)))
             }
+
+            None => Ok(Value::String(full_help(state))),
+        }
+    },
+};
+
+const IF: FunctionDefinition = FunctionDefinition {
+    name: "if",
+    category: None,
+    description:
+        "Returns then if the condition is true, or otherwise. Only the returned branch is evaluated",
+    arguments: || {
+        vec![
+            FunctionArgument::new_required("condition", ExpectedTypes::Any),
+            FunctionArgument::new_required("then", ExpectedTypes::Any),
+            FunctionArgument::new_required("otherwise", ExpectedTypes::Any),
+        ]
+    },
+    handler: |_function, _token, _state, args| {
+        if args.get("condition").required().as_bool() {
+            Ok(args.get("then").required())
+        } else {
+            Ok(args.get("otherwise").required())
         }
     },
 };
@@ -142,6 +192,7 @@ const CALL: FunctionDefinition = FunctionDefinition {
 /// Register api functions
 pub fn register_functions(table: &mut FunctionTable) {
     table.register(HELP);
+    table.register(IF);
     table.register(RUN);
     table.register(CALL);
 }
@@ -186,6 +237,67 @@ mod test_token {
         assert_eq!(true, state.user_functions.contains_key("factorial"));
     }
 
+    #[test]
+    fn test_if() {
+        let mut state = ParserState::new();
+        assert_eq!(
+            Value::Integer(2),
+            Token::new("if(false, 1/0, 2)", &mut state).unwrap().value()
+        );
+        assert_eq!(
+            Value::Integer(1),
+            Token::new("if(1 < 2, 1, 2/0)", &mut state).unwrap().value()
+        );
+        assert_eq!(
+            Value::from("b"),
+            IF.call(
+                &Token::dummy(""),
+                &mut state,
+                &[Value::Boolean(false), Value::from("a"), Value::from("b")]
+            )
+            .unwrap()
+        );
+        assert!(matches!(
+            Token::new("if(true, 1/0, 2)", &mut state),
+            Err(Error::DivideByZero(_))
+        ));
+        assert!(matches!(
+            Token::new("if(true, undefined_variable, 2)", &mut state),
+            Err(Error::VariableName { .. })
+        ));
+        assert!(matches!(
+            Token::new("if(true, 1)", &mut state),
+            Err(Error::FunctionArguments { .. })
+        ));
+        assert!(matches!(
+            Token::new("if(1/0, 1)", &mut state),
+            Err(Error::FunctionArguments { .. })
+        ));
+
+        // The call still goes through limits, read-only mode and the call hook
+        state.set_max_string_length(Some(3));
+        assert!(matches!(
+            Token::new("if(true, 'abcd', 2)", &mut state),
+            Err(Error::Overflow(_))
+        ));
+        state.set_read_only(true);
+        assert!(matches!(
+            Token::new("if(true, run('x = 1'), 2)", &mut state),
+            Err(Error::ReadOnly(_))
+        ));
+        state.set_on_call(|name, _args| {
+            if name == "if" {
+                Some(Ok(Value::Integer(42)))
+            } else {
+                None
+            }
+        });
+        assert_eq!(
+            Value::Integer(42),
+            Token::new("if(true, 1, 2/0)", &mut state).unwrap().value()
+        );
+    }
+
     #[test]
     fn test_run() {
         let mut state = ParserState::new();
@@ -261,6 +373,14 @@ mod test_token {
                 .contains("Built-in Decorators")
         );
 
+        assert_eq!(
+            true,
+            HELP.call(&Token::dummy(""), &mut state, &[])
+                .unwrap()
+                .as_string()
+                .contains("XOR (true ^^ false)")
+        );
+
         println!(
             "{}",
             HELP.call(&Token::dummy(""), &mut state, &[])
@@ -313,4 +433,34 @@ mod test_token {
             Token::new("help(test)", &mut state).unwrap().text()
         );
     }
+
+    #[test]
+    fn test_help_lookup() {
+        let mut state = ParserState::new();
+        let help = |state: &mut ParserState, target: &str| {
+            HELP.call(&Token::dummy(""), state, &[Value::from(target)])
+                .unwrap()
+                .as_string()
+        };
+
+        // Exact match
+        assert_eq!(
+            "sqrt(n): Returns the square root of n",
+            help(&mut state, "sqrt")
+        );
+
+        // Category match
+        let arrays = help(&mut state, "arrays");
+        assert!(arrays.contains("len(input)"));
+        assert!(arrays.contains("pop(array)"));
+        assert!(!arrays.contains("sqrt(n)"));
+
+        // Near-miss suggestion
+        let suggestion = help(&mut state, "sqr");
+        assert!(suggestion.starts_with("Did you mean:"));
+        assert!(suggestion.contains("sqrt(n)"));
+
+        // No match at all
+        assert!(help(&mut state, "zzzz").contains("Math Functions"));
+    }
 }
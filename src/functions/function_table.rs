@@ -1,17 +1,20 @@
 use crate::Error;
 use crate::{ParserState, Token, Value};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use super::builtins;
-use super::FunctionDefinition;
+use super::{FunctionArgument, FunctionDefinition};
 
 /// Holds a set of callable functions
+/// Clones share the same underlying map until one of them is modified, which keeps
+/// the per-call state clones made by user functions cheap
 #[derive(Clone)]
-pub struct FunctionTable(HashMap<String, FunctionDefinition>);
+pub struct FunctionTable(Arc<HashMap<String, FunctionDefinition>>);
 impl FunctionTable {
     /// Initialize a new function table, complete with default builtin functions
     pub fn new() -> FunctionTable {
-        let mut table: FunctionTable = FunctionTable(HashMap::new());
+        let mut table: FunctionTable = FunctionTable(Arc::new(HashMap::new()));
         table.register_builtins();
         table
     }
@@ -21,9 +24,11 @@ impl FunctionTable {
         builtins::api::register_functions(self);
         builtins::array::register_functions(self);
         builtins::crypto::register_functions(self);
+        builtins::csv::register_functions(self);
         builtins::dev::register_functions(self);
         builtins::math::register_functions(self);
         builtins::network::register_functions(self);
+        builtins::stats::register_functions(self);
         builtins::system::register_functions(self);
         builtins::str::register_functions(self);
         builtins::trig::register_functions(self);
@@ -35,7 +40,7 @@ impl FunctionTable {
     /// * `name` - Function name
     /// * `handler` - Function handler
     pub fn register(&mut self, function: FunctionDefinition) {
-        self.0.insert(function.name.to_string(), function);
+        Arc::make_mut(&mut self.0).insert(function.name.to_string(), function);
     }
 
     /// Remove a function from the table
@@ -43,7 +48,7 @@ impl FunctionTable {
     /// # Arguments
     /// * `name` - Function name
     pub fn remove(&mut self, name: &str) {
-        self.0.remove(&name.to_string());
+        Arc::make_mut(&mut self.0).remove(&name.to_string());
     }
 
     /// Check if the table contains a function by the given name
@@ -77,24 +82,23 @@ impl FunctionTable {
         v
     }
 
+    /// Return all included functions in a given category, sorted by name
+    ///
+    /// # Arguments
+    /// * `category` - Category name
+    pub fn by_category(&self, category: &str) -> Vec<&FunctionDefinition> {
+        self.all()
+            .into_iter()
+            .filter(|f| f.category() == category)
+            .collect()
+    }
+
     /// Return all included functions sorted by category
     pub fn all_by_category(&self) -> HashMap<&str, Vec<&FunctionDefinition>> {
-        let f: Vec<(&str, Vec<&FunctionDefinition>)> = self
-            .all_categories()
-            .iter()
-            .map(|c| {
-                (
-                    *c,
-                    self.all()
-                        .iter()
-                        .filter(|f| f.category() == *c)
-                        .copied()
-                        .collect::<Vec<&FunctionDefinition>>(),
-                )
-            })
-            .collect();
-        let m: HashMap<_, _> = f.into_iter().collect();
-        m
+        self.all_categories()
+            .into_iter()
+            .map(|c| (c, self.by_category(c)))
+            .collect()
     }
 
     /// Call a function
@@ -126,6 +130,14 @@ impl FunctionTable {
         self.0.get(name).map(|f| f.signature())
     }
 
+    /// Return the arguments a function expects, in order
+    ///
+    /// # Arguments
+    /// * `name` - Function name
+    pub fn arguments(&self, name: &str) -> Option<Vec<FunctionArgument>> {
+        self.0.get(name).map(|f| f.args())
+    }
+
     /// Return a function's description
     ///
     /// # Arguments
@@ -140,3 +152,20 @@ impl Default for FunctionTable {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod test_function_table {
+    use super::*;
+
+    #[test]
+    fn test_clone_is_shared() {
+        let table = FunctionTable::new();
+        let mut inner = table.clone();
+        assert!(Arc::ptr_eq(&table.0, &inner.0));
+
+        inner.remove("sqrt");
+        assert!(!Arc::ptr_eq(&table.0, &inner.0));
+        assert!(table.has("sqrt"));
+        assert!(!inner.has("sqrt"));
+    }
+}
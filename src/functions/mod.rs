@@ -75,4 +75,39 @@ mod test_builtin_table {
             .call("example", &token, &mut state, &[Value::Integer(4)])
             .unwrap();
     }
+
+    #[test]
+    fn test_signature() {
+        let table = FunctionTable::new();
+        assert_eq!(
+            Some("round(n, [precision])".to_string()),
+            table.signature("round")
+        );
+        assert_eq!(None, table.signature("not_a_function"));
+
+        let args = table.arguments("round").unwrap();
+        assert_eq!(
+            vec![("n", false), ("precision", true)],
+            args.iter()
+                .map(|a| (a.name(), a.optional()))
+                .collect::<Vec<(&str, bool)>>()
+        );
+    }
+
+    #[test]
+    fn test_by_category() {
+        let table = FunctionTable::new();
+        assert!(table.all_categories().contains(&"arrays"));
+
+        let arrays = table
+            .by_category("arrays")
+            .iter()
+            .map(|f| f.name())
+            .collect::<Vec<&str>>();
+        for name in ["len", "push", "pop"] {
+            assert!(arrays.contains(&name));
+        }
+        assert!(!arrays.contains(&"sqrt"));
+        assert!(table.by_category("not_a_category").is_empty());
+    }
 }
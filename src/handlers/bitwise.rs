@@ -4,9 +4,15 @@ use super::{perform_int_calculation, RuleHandler};
 use crate::{
     state::ParserState,
     token::{Rule, Token},
-    Error, ExpectedTypes, IntegerType,
+    Error, ExpectedTypes, IntegerType, Value,
 };
 
+/// Determine if a value, or any element of an array, is a float
+/// Bitwise operations reject floats whether they are scalars or array elements
+fn contains_float(value: &Value) -> bool {
+    value.as_array().iter().any(|e| e.is_float())
+}
+
 pub fn handler_table() -> HashMap<Rule, RuleHandler> {
     HashMap::from([
         (Rule::sh_expression, rule_sh_expression as RuleHandler),
@@ -26,18 +32,23 @@ fn rule_sh_expression(token: &mut Token, _state: &mut ParserState) -> Option<Err
         let mut i = 2;
         while i < token.children().len() {
             let ih = match token.child(i - 1).unwrap().rule() {
-                Rule::lshift => |l: IntegerType, r: IntegerType| Some(l << r),
-                Rule::rshift => |l: IntegerType, r: IntegerType| Some(l >> r),
+                // Shifts by a negative amount, or by the width of the type or more, overflow
+                Rule::lshift => |l: IntegerType, r: IntegerType| {
+                    u32::try_from(r).ok().and_then(|r| l.checked_shl(r))
+                },
+                Rule::rshift => |l: IntegerType, r: IntegerType| {
+                    u32::try_from(r).ok().and_then(|r| l.checked_shr(r))
+                },
                 _ => return Some(Error::Internal(token.clone())),
             };
 
-            if token.value().is_float() {
+            if contains_float(&token.value()) {
                 return Some(Error::ValueType {
                     value: token.value(),
                     expected_type: ExpectedTypes::Int,
                     token: token.clone(),
                 });
-            } else if token.child(i).unwrap().value().is_float() {
+            } else if contains_float(&token.child(i).unwrap().value()) {
                 let token = token.child(i).unwrap();
                 return Some(Error::ValueType {
                     value: token.value(),
@@ -67,7 +78,7 @@ fn rule_and_expression(token: &mut Token, _state: &mut ParserState) -> Option<Er
     if token.children().len() > 1 {
         let mut i = 2;
         while i < token.children().len() {
-            if token.value().is_float() || token.child(i).unwrap().value().is_float() {
+            if contains_float(&token.value()) || contains_float(&token.child(i).unwrap().value()) {
                 let token = token.child(i).unwrap();
                 return Some(Error::ValueType {
                     value: token.value(),
@@ -101,7 +112,7 @@ fn rule_xor_expression(token: &mut Token, _state: &mut ParserState) -> Option<Er
     if token.children().len() > 1 {
         let mut i = 2;
         while i < token.children().len() {
-            if token.value().is_float() || token.child(i).unwrap().value().is_float() {
+            if contains_float(&token.value()) || contains_float(&token.child(i).unwrap().value()) {
                 return Some(Error::ValueType {
                     value: token.value(),
                     expected_type: ExpectedTypes::Int,
@@ -134,7 +145,7 @@ fn rule_or_expression(token: &mut Token, _state: &mut ParserState) -> Option<Err
     if token.children().len() > 1 {
         let mut i = 2;
         while i < token.children().len() {
-            if token.value().is_float() || token.child(i).unwrap().value().is_float() {
+            if contains_float(&token.value()) || contains_float(&token.child(i).unwrap().value()) {
                 return Some(Error::ValueType {
                     value: token.value(),
                     expected_type: ExpectedTypes::Int,
@@ -185,10 +196,18 @@ mod test_token {
         assert_token_value!("2 << 2", Value::from(8));
         assert_token_value!("2 << 2 >> 2", Value::from(2));
 
+        // Out of range shifts
+        assert_token_error!("1 << 100", Overflow);
+        assert_token_error!("1 << -1", Overflow);
+        assert_token_error!("1 >> 64", Overflow);
+        assert_token_error!("1 >> -1", Overflow);
+
         // Other typed values
         assert_token_error!("4.0 >> 1", ValueType);
         assert_token_error!("false >> 1.0", ValueType);
         assert_token_error!("4.0 >> 'test'", ValueType);
+        assert_token_error!("[4.0, 2] >> 1", ValueType);
+        assert_token_error!("4 << [1, 2.0]", ValueType);
     }
 
     #[test]
@@ -216,6 +235,8 @@ mod test_token {
         assert_token_error!("4.0 & 1", ValueType);
         assert_token_error!("false & 1", ValueType);
         assert_token_error!("4 & 'test'", ValueType);
+        assert_token_error!("[4.5, 2] & 1", ValueType);
+        assert_token_error!("1 & [4, 2.5]", ValueType);
 
         let mut state = ParserState::new();
 
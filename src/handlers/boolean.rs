@@ -17,6 +17,10 @@ pub fn handler_table() -> HashMap<Rule, RuleHandler> {
             Rule::bool_and_expression,
             rule_bool_and_expression as RuleHandler,
         ),
+        (
+            Rule::bool_xor_expression,
+            rule_bool_xor_expression as RuleHandler,
+        ),
         (
             Rule::bool_or_expression,
             rule_bool_or_expression as RuleHandler,
@@ -67,6 +71,22 @@ fn rule_bool_and_expression(token: &mut Token, _state: &mut ParserState) -> Opti
     None
 }
 
+/// A boolean xor expression
+/// a ^^ b
+fn rule_bool_xor_expression(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
+    let mut i = 0;
+    token.set_value(token.child(i).unwrap().value());
+    while i < token.children().len() - 2 {
+        token.set_value(Value::Boolean(
+            token.value().as_bool() ^ token.child(i + 2).unwrap().value().as_bool(),
+        ));
+        i += 2
+    }
+
+    token.set_format(OutputFormat::Default); // Revert to boolean type
+    None
+}
+
 /// A boolean or expression
 /// a || b
 fn rule_bool_or_expression(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
@@ -136,6 +156,10 @@ mod test_token {
         assert_token_value!("1.3 <= 1.3", Value::from(true));
 
         assert_token_value!("'test' == 1", Value::from(false));
+
+        assert_token_value!("{'a': 1} == {'b': 1}", Value::from(false));
+        assert_token_value!("{'a': 1} != {'b': 1}", Value::from(true));
+        assert_token_value!("{'a': 1, 'b': 2} == {'b': 2, 'a': 1}", Value::from(true));
     }
 
     #[test]
@@ -148,6 +172,23 @@ mod test_token {
         assert_token_value!("true && true && true && false", Value::from(false));
     }
 
+    #[test]
+    fn rule_bool_xor_expression() {
+        assert_token_value!("false ^^ false", Value::from(false));
+        assert_token_value!("false ^^ true", Value::from(true));
+        assert_token_value!("true ^^ false", Value::from(true));
+        assert_token_value!("true ^^ true", Value::from(false));
+        assert_token_value!("true ^^ true ^^ true", Value::from(true));
+        assert_token_value!("1 ^^ 0", Value::from(true));
+
+        // Binds tighter than || and looser than &&
+        assert_token_value!("true || true ^^ true", Value::from(true));
+        assert_token_value!("true ^^ true && false", Value::from(true));
+
+        // Distinct from bitwise xor
+        assert_token_value!("3 ^ 1", Value::Integer(2));
+    }
+
     #[test]
     fn rule_bool_or_expression() {
         assert_token_value!("false || false", Value::from(false));
@@ -157,4 +198,21 @@ mod test_token {
         assert_token_value!("false || false || false || false", Value::from(false));
         assert_token_value!("false || false || false || true", Value::from(true));
     }
+
+    #[test]
+    fn test_short_circuit() {
+        assert_token_value!("false && 1/0", Value::from(false));
+        assert_token_value!("true || 1/0", Value::from(true));
+        assert_token_value!("true && false && 1/0", Value::from(false));
+        assert_token_value!("false || true || undefined_var", Value::from(true));
+        assert_token_error!("true && 1/0", DivideByZero);
+        assert_token_error!("false || 1/0", DivideByZero);
+
+        // Skipped operands have no side effects
+        let mut state = ParserState::new();
+        assert_token_value_stateful!("false && run('x = 5')", Value::from(false), &mut state);
+        assert!(!state.variables.contains_key("x"));
+        assert_token_value_stateful!("true && run('x = 5')", Value::from(true), &mut state);
+        assert_eq!(Value::Integer(5), state.variables["x"]);
+    }
 }
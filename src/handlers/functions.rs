@@ -11,7 +11,7 @@ pub fn handler_table() -> HashMap<Rule, RuleHandler> {
     HashMap::from([(Rule::call_expression, rule_call_expression as RuleHandler)])
 }
 
-fn rule_call_expression(token: &mut Token, state: &mut ParserState) -> Option<Error> {
+pub fn rule_call_expression(token: &mut Token, state: &mut ParserState) -> Option<Error> {
     // Get function name and arguments
     let name = &token.child(0).unwrap().text().to_string();
     let mut arg_tokens = Vec::<&Token>::new();
@@ -35,6 +35,25 @@ fn rule_call_expression(token: &mut Token, state: &mut ParserState) -> Option<Er
         }
     }
 
+    if let Err(e) = state.charge_call(token) {
+        return Some(e);
+    }
+    state.record_call(name);
+
+    // Call interception hook
+    if let Some(hook) = state.on_call.clone() {
+        if let Ok(mut hook) = hook.lock() {
+            match (*hook)(name, &args) {
+                Some(Ok(v)) => {
+                    token.set_value(v);
+                    return None;
+                }
+                Some(Err(e)) => return Some(e),
+                None => {}
+            }
+        }
+    }
+
     // Extension functions
     #[cfg(feature = "extensions")]
     if state.extensions.has_function(name) {
@@ -55,6 +74,9 @@ fn rule_call_expression(token: &mut Token, state: &mut ParserState) -> Option<Er
         let functions = state.functions.clone();
         match functions.call(name, token, state, &args) {
             Ok(v) => {
+                if let Err(e) = state.check_value_size(&v, token) {
+                    return Some(e);
+                }
                 token.set_value(v);
                 return None;
             }
@@ -72,15 +94,18 @@ fn rule_call_expression(token: &mut Token, state: &mut ParserState) -> Option<Er
                 token: token.clone(),
             });
         } else if let Some(mut inner_state) = state.spawn_inner() {
+            // The body runs in its own copy of the state - it can read the caller's variables,
+            // but anything it assigns is discarded along with inner_state once the call returns
+
             // Populate arguments
             for (i, arg) in f.arguments().clone().into_iter().enumerate() {
                 inner_state.variables.insert(arg, args[i].clone());
             }
 
-            // Run the function as an expression
-            match Token::new(f.definition(), &mut inner_state) {
-                Ok(t) => {
-                    token.set_value(t.value());
+            // Run the function as an expression - block bodies return their last line
+            match inner_state.eval(f.definition()) {
+                Ok(v) => {
+                    token.set_value(v);
                     return None;
                 }
                 Err(e) => return Some(e),
@@ -100,6 +125,7 @@ fn rule_call_expression(token: &mut Token, state: &mut ParserState) -> Option<Er
 mod test_token {
     use super::*;
     use crate::test::*;
+    use crate::value::ObjectType;
 
     #[test]
     fn test_builtin_function_call() {
@@ -111,6 +137,20 @@ mod test_token {
         assert_token_value!("root(9, 2)", Value::Float(3.0));
     }
 
+    #[test]
+    fn test_on_call_hook() {
+        let mut state: ParserState = ParserState::new();
+        state.set_on_call(|name, _args| {
+            if name == "sqrt" {
+                Some(Ok(Value::Integer(42)))
+            } else {
+                None
+            }
+        });
+        assert_token_value_stateful!("sqrt(9)", Value::Integer(42), &mut state);
+        assert_token_value_stateful!("root(9, 2)", Value::Float(3.0), &mut state);
+    }
+
     #[test]
     fn test_user_function_call() {
         let mut state: ParserState = ParserState::new();
@@ -126,6 +166,57 @@ mod test_token {
         assert_token_value_stateful!("sum([10, 10, 11])", Value::Integer(31), &mut state);
     }
 
+    #[test]
+    fn test_user_function_scope() {
+        let mut state: ParserState = ParserState::new();
+        Token::new("y = 5\nf(x) = run('tmp = ' + x) + y", &mut state).unwrap();
+        assert_token_value_stateful!("f(2)", Value::Integer(7), &mut state);
+        assert!(!state.variables.contains_key("tmp"));
+
+        // Arguments and assignments shadow the caller's variables without modifying them
+        Token::new("g(y) = run('y = y * 10') + y", &mut state).unwrap();
+        assert_token_value_stateful!("g(1)", Value::Integer(20), &mut state);
+        assert_eq!(Value::Integer(5), state.variables["y"]);
+        assert!(!state.variables.contains_key("x"));
+
+        // Functions defined inside a call stay local too
+        Token::new("h() = run('inner() = 1')", &mut state).unwrap();
+        assert_token_value_stateful!("h()", Value::from("1"), &mut state);
+        assert!(!state.user_functions.contains_key("inner"));
+    }
+
+    #[test]
+    fn test_user_function_block() {
+        let mut state: ParserState = ParserState::new();
+        assert_token_text_stateful!(
+            "f(x) = { y = x*2; y + 1 }",
+            "{ y = x*2; y + 1 }",
+            &mut state
+        );
+        assert_eq!("y = x*2; y + 1", state.user_functions["f"].definition());
+        assert_token_value_stateful!("f(5)", Value::Integer(11), &mut state);
+        assert!(!state.variables.contains_key("y"));
+
+        Token::new("g(a, b) = {\n  c = a + b\n  c * 2\n}", &mut state).unwrap();
+        assert_token_value_stateful!("g(1, 2)", Value::Integer(6), &mut state);
+
+        // Object literals are still valid function bodies
+        Token::new("h(x) = {'x': x}", &mut state).unwrap();
+        assert_eq!(
+            Value::Integer(1),
+            state.eval("h(1)").unwrap().as_object()[&Value::from("x")]
+        );
+
+        // Only the outer braces are removed from a block ending in an object literal
+        Token::new("g(x) = { y = x; {'v': y}}", &mut state).unwrap();
+        assert_eq!("y = x; {'v': y}", state.user_functions["g"].definition());
+        assert_token_value_stateful!(
+            "g(3)",
+            Value::Object(ObjectType::from([(Value::from("v"), Value::Integer(3))])),
+            &mut state
+        );
+    }
+
     #[test]
     #[cfg(feature = "extensions")]
     fn test_extension_function_call() {
@@ -1,6 +1,11 @@
 use std::collections::HashMap;
 
 use super::{perform_calculation, RuleHandler};
+
+#[cfg(feature = "bigint")]
+use super::{perform_bigint_calculation, BigIntHandler};
+#[cfg(feature = "bigint")]
+use crate::BigIntType;
 use crate::{
     state::ParserState,
     token::{Rule, Token},
@@ -182,7 +187,40 @@ pub fn handler_table() -> HashMap<Rule, RuleHandler> {
     ])
 }
 
-fn rule_as_expression(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
+/// Perform arbitrary-precision division, failing on division by zero
+///
+/// # Arguments
+/// * `l` - Left value
+/// * `r` - Right value
+#[cfg(feature = "bigint")]
+fn bigint_checked_div(l: &BigIntType, r: &BigIntType) -> Option<BigIntType> {
+    if r.sign() == num_bigint::Sign::NoSign {
+        None
+    } else {
+        Some(l / r)
+    }
+}
+
+/// Perform arbitrary-precision euclidean remainder, failing on division by zero
+///
+/// # Arguments
+/// * `l` - Left value
+/// * `r` - Right value
+#[cfg(feature = "bigint")]
+fn bigint_checked_rem_euclid(l: &BigIntType, r: &BigIntType) -> Option<BigIntType> {
+    if r.sign() == num_bigint::Sign::NoSign {
+        return None;
+    }
+
+    let remainder = l % r;
+    if remainder.sign() == num_bigint::Sign::Minus {
+        Some(remainder + BigIntType::from(r.magnitude().clone()))
+    } else {
+        Some(remainder)
+    }
+}
+
+fn rule_as_expression(token: &mut Token, state: &mut ParserState) -> Option<Error> {
     token.set_value(token.child(0).unwrap().value());
     if token.children().len() > 1 {
         let mut i = 2;
@@ -190,12 +228,30 @@ fn rule_as_expression(token: &mut Token, _state: &mut ParserState) -> Option<Err
             match token.child(i - 1).unwrap().rule() {
                 Rule::plus => {
                     if token.value().is_string() || token.child(i).unwrap().value().is_string() {
-                        token.set_value(Value::String(format!(
-                            "{}{}",
+                        let (l, r) = (
                             token.value().as_string(),
-                            token.child(i).unwrap().value().as_string()
-                        )));
+                            token.child(i).unwrap().value().as_string(),
+                        );
+                        if let Err(e) = state.check_string_length(l.len() + r.len(), token) {
+                            return Some(e);
+                        }
+                        token.set_value(Value::String(format!("{}{}", l, r)));
                     } else {
+                        #[cfg(feature = "bigint")]
+                        if let Some(result) = perform_bigint_calculation(
+                            token,
+                            &token.value(),
+                            &token.child(i).unwrap().value(),
+                            |l, r| Some(l + r),
+                        ) {
+                            match result {
+                                Ok(n) => token.set_value(n),
+                                Err(e) => return Some(e),
+                            }
+                            i += 2;
+                            continue;
+                        }
+
                         match perform_calculation(
                             token,
                             token.value(),
@@ -210,6 +266,21 @@ fn rule_as_expression(token: &mut Token, _state: &mut ParserState) -> Option<Err
                 }
 
                 Rule::minus => {
+                    #[cfg(feature = "bigint")]
+                    if let Some(result) = perform_bigint_calculation(
+                        token,
+                        &token.value(),
+                        &token.child(i).unwrap().value(),
+                        |l, r| Some(l - r),
+                    ) {
+                        match result {
+                            Ok(n) => token.set_value(n),
+                            Err(e) => return Some(e),
+                        }
+                        i += 2;
+                        continue;
+                    }
+
                     match perform_calculation(
                         token,
                         token.value(),
@@ -232,7 +303,42 @@ fn rule_as_expression(token: &mut Token, _state: &mut ParserState) -> Option<Err
     None
 }
 
-fn rule_implied_mul_expression(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
+/// Repeat a string, if one operand of a multiplication is a string and the other an integer
+/// Returns None for any other pair of operands
+///
+/// # Arguments
+/// * `token` - Source token
+/// * `state` - Parser state, for string length limits
+/// * `l` - Left operand
+/// * `r` - Right operand
+fn repeat_string(
+    token: &Token,
+    state: &ParserState,
+    l: &Value,
+    r: &Value,
+) -> Option<Result<Value, Error>> {
+    let (s, n) = match (l, r) {
+        (Value::String(s), Value::Integer(n)) | (Value::Integer(n), Value::String(s)) => (s, *n),
+        _ => return None,
+    };
+
+    if n < 0 {
+        return Some(Err(Error::ValueType {
+            value: Value::Integer(n),
+            expected_type: ExpectedTypes::Int,
+            token: token.clone(),
+        }));
+    }
+
+    Some(match s.len().checked_mul(n as usize) {
+        Some(length) => state
+            .check_string_length(length, token)
+            .map(|_| Value::String(s.repeat(n as usize))),
+        None => Err(Error::Overflow(token.clone())),
+    })
+}
+
+fn rule_implied_mul_expression(token: &mut Token, state: &mut ParserState) -> Option<Error> {
     token.set_value(token.child(0).unwrap().value());
     if token.children().len() > 1 {
         let mut i = 1;
@@ -242,6 +348,20 @@ fn rule_implied_mul_expression(token: &mut Token, _state: &mut ParserState) -> O
                 continue;
             }
 
+            if let Some(result) = repeat_string(
+                token,
+                state,
+                &token.value(),
+                &token.child(i).unwrap().value(),
+            ) {
+                match result {
+                    Ok(v) => token.set_value(v),
+                    Err(e) => return Some(e),
+                }
+                i += 1;
+                continue;
+            }
+
             let ih = IntegerType::checked_mul;
             let fh = |l: FloatType, r: FloatType| l * r;
 
@@ -263,12 +383,28 @@ fn rule_implied_mul_expression(token: &mut Token, _state: &mut ParserState) -> O
     None
 }
 
-fn rule_md_expression(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
+fn rule_md_expression(token: &mut Token, state: &mut ParserState) -> Option<Error> {
     token.set_value(token.child(0).unwrap().value());
 
     if token.children().len() > 1 {
         let mut i = 2;
         while i < token.children().len() {
+            if token.child(i - 1).unwrap().rule() == Rule::multiply {
+                if let Some(result) = repeat_string(
+                    token,
+                    state,
+                    &token.value(),
+                    &token.child(i).unwrap().value(),
+                ) {
+                    match result {
+                        Ok(v) => token.set_value(v),
+                        Err(e) => return Some(e),
+                    }
+                    i += 2;
+                    continue;
+                }
+            }
+
             let ih = match token.child(i - 1).unwrap().rule() {
                 Rule::multiply => IntegerType::checked_mul,
                 Rule::divide => IntegerType::checked_div,
@@ -283,6 +419,41 @@ fn rule_md_expression(token: &mut Token, _state: &mut ParserState) -> Option<Err
                 _ => return Some(Error::Internal(token.clone())),
             };
 
+            // Catch zero divisors before they surface as overflows
+            if token.child(i - 1).unwrap().rule() != Rule::multiply
+                && token
+                    .child(i)
+                    .unwrap()
+                    .value()
+                    .as_array()
+                    .iter()
+                    .any(|v| v.as_float() == Some(0.0))
+            {
+                return Some(Error::DivideByZero(token.clone()));
+            }
+
+            #[cfg(feature = "bigint")]
+            {
+                let bh: BigIntHandler = match token.child(i - 1).unwrap().rule() {
+                    Rule::multiply => |l, r| Some(l * r),
+                    Rule::divide => bigint_checked_div,
+                    _ => bigint_checked_rem_euclid,
+                };
+                if let Some(result) = perform_bigint_calculation(
+                    token,
+                    &token.value(),
+                    &token.child(i).unwrap().value(),
+                    bh,
+                ) {
+                    match result {
+                        Ok(n) => token.set_value(n),
+                        Err(e) => return Some(e),
+                    }
+                    i += 2;
+                    continue;
+                }
+            }
+
             match perform_calculation(
                 token,
                 token.value(),
@@ -307,6 +478,21 @@ fn rule_power_expression(token: &mut Token, _state: &mut ParserState) -> Option<
     if token.children().len() > 1 {
         let mut i = 2;
         while i < token.children().len() {
+            #[cfg(feature = "bigint")]
+            if let Some(result) = perform_bigint_calculation(
+                token,
+                &token.value(),
+                &token.child(i).unwrap().value(),
+                |l, r| u32::try_from(r).ok().map(|r| l.pow(r)),
+            ) {
+                match result {
+                    Ok(n) => token.set_value(n),
+                    Err(e) => return Some(e),
+                }
+                i += 2;
+                continue;
+            }
+
             match perform_calculation(
                 token,
                 token.value(),
@@ -375,6 +561,24 @@ mod test_token {
     use super::*;
     use crate::test::*;
 
+    #[test]
+    fn test_rule_md_expression() {
+        assert_token_value!("6 / 2 * 3 % 4", Value::Integer(1));
+        assert_token_value!("5.0 / 2", Value::Float(2.5));
+
+        assert_token_error!("5 % 0", DivideByZero);
+        assert_token_error!("5 / 0", DivideByZero);
+        assert_token_error!("5.0 / 0.0", DivideByZero);
+        assert_token_error!("[4, 6] / [2, 0]", DivideByZero);
+        assert_eq!(
+            true,
+            Token::new("5 % 0", &mut ParserState::new())
+                .unwrap_err()
+                .to_string()
+                .starts_with("division or modulo by zero")
+        );
+    }
+
     #[test]
     fn test_integer_type_checked_pow() {
         assert_eq!(1, integer_type_checked_pow(10, 0).unwrap());
@@ -539,6 +743,19 @@ mod test_token {
         assert_token_value!("12%10 * 2 / 2", Value::from(2));
     }
 
+    #[test]
+    fn test_string_multiplication() {
+        let mut state = ParserState::new();
+        assert_token_value!("'ab' * 3", Value::from("ababab"));
+        assert_token_value!("3 * \"ab\"", Value::from("ababab"));
+        assert_token_value!("'ab' * 0", Value::from(""));
+        assert_token_value!("'ab' * 2 * 2", Value::from("abababab"));
+        assert_token_error!("'ab' * -1", ValueType);
+
+        state.set_max_string_length(Some(5));
+        assert_token_error_stateful!("'ab' * 3", Overflow, &mut state);
+    }
+
     #[test]
     fn test_implied_mul_expression() {
         let mut state = ParserState::new();
@@ -16,74 +16,57 @@ mod functions;
 mod math;
 mod values;
 
+pub use math::factorial;
+pub use values::parse_radix;
+
 #[derive(Default)]
 pub struct Handler {}
 impl LavendeuxHandler for Handler {
     fn handle_tree(&self, token: &mut Token, state: &mut ParserState) -> Result<(), Error> {
+        state.charge_step(token)?;
+
+        // Read-only mode - reject assignments before anything is evaluated
+        if matches!(
+            token.rule(),
+            Rule::assignment_expression | Rule::function_assignment
+        ) {
+            state.check_writable(token)?;
+        }
+
         // Ternary expression handler - enables short-circuit interpretation
         if token.rule() == Rule::ternary_expression {
-            let condition = token.mut_child(0).unwrap();
-            self.handle_tree(condition, state)?;
-
-            let path_index = if condition.value().as_bool() { 1 } else { 2 };
-            self.handle_tree(token.mut_child(path_index).unwrap(), state)?;
+            return self.handle_ternary(token, state);
+        }
 
-            let child = token.child(path_index).unwrap().clone();
-            token.set_format(child.format());
-            token.set_text(child.text());
-            token.set_value(child.value());
-            return Ok(());
+        // Conditional function handler - enables short-circuit interpretation of if()
+        if token.rule() == Rule::call_expression && is_builtin_if(token, state) {
+            return self.handle_if(token, state);
         }
 
         // Function assignment handler - prevents prematurely executing the new function
         if token.rule() == Rule::function_assignment {
-            let name = token.children().first().unwrap().text();
-            let definition = token.children().last().unwrap().text();
-
-            // Compile arguments
-            let mut arguments: Vec<String> = Vec::new();
-            for child in token.children().iter().skip(2) {
-                let s = child.text();
-                if s == "," {
-                    continue;
-                }
-                if s == ")" {
-                    break;
-                }
-                arguments.push(s.to_string());
-            }
-
-            // Store new function
-            state.user_functions.insert(
-                name.to_string(),
-                UserFunction::new(name.to_string(), arguments, definition.to_string()),
-            );
-
-            let def = token.children().last().unwrap().clone();
-            token.set_text(def.text());
-            token.set_value(Value::String(def.text().to_string()));
+            handle_function_assignment(token, state);
             return Ok(());
         }
 
+        // Boolean and/or stop at the first operand that decides the result - enables short-circuit
+        // interpretation. Skipped operands keep a value of none, which the handlers treat as false
+        let short_circuit = match token.rule() {
+            Rule::bool_and_expression => Some(false),
+            Rule::bool_or_expression => Some(true),
+            _ => None,
+        };
+
         // Handle child nodes
-        for child in token.mut_children() {
+        for (i, child) in token.mut_children().iter_mut().enumerate() {
             self.handle_tree(child, state)?;
+            if i.is_multiple_of(2) && short_circuit == Some(child.value().as_bool()) {
+                break;
+            }
         }
 
         // Check for unresolve identifier errors
-        for child in token.children() {
-            if child.value().is_identifier() {
-                // Help function is allowed to have an unresolved identifier
-                if !(token.rule() == Rule::call_expression
-                    && token.child(0).unwrap().text() == "help")
-                {
-                    return Err(Error::VariableName {
-                        name: child.text().to_string(),
-                        token: child.clone(),
-                    });
-                }
-            }
-        }
+        check_identifiers(token)?;
 
         // Bubble up output format from children
         let format = token.children().iter().fold(OutputFormat::Default, |a, f| {
@@ -106,6 +89,135 @@ impl LavendeuxHandler for Handler {
     }
 }
 
+impl Handler {
+    /// Ternary expressions only evaluate the branch that was chosen
+    #[inline(never)]
+    fn handle_ternary(&self, token: &mut Token, state: &mut ParserState) -> Result<(), Error> {
+        let condition = token.mut_child(0).unwrap();
+        self.handle_tree(condition, state)?;
+
+        let path_index = if condition.value().as_bool() { 1 } else { 2 };
+        self.handle_tree(token.mut_child(path_index).unwrap(), state)?;
+
+        let child = token.child(path_index).unwrap().clone();
+        token.set_format(child.format());
+        token.set_text(child.text());
+        token.set_value(child.value());
+        Ok(())
+    }
+
+    /// Like the ternary, only the chosen branch of if() is evaluated. The call itself then
+    /// runs like any other, so hooks and limits still apply. Any other number of arguments
+    /// is left unevaluated, for the builtin to reject
+    ///
+    /// Kept out of handle_tree so that its locals do not grow every recursive frame
+    #[inline(never)]
+    fn handle_if(&self, token: &mut Token, state: &mut ParserState) -> Result<(), Error> {
+        let arguments = token.mut_child(2).unwrap();
+        if arguments.rule() == Rule::expression_list && arguments.children().len() == 5 {
+            let condition = arguments.mut_child(0).unwrap();
+            self.handle_tree(condition, state)?;
+
+            let path_index = if condition.value().as_bool() { 2 } else { 4 };
+            self.handle_tree(arguments.mut_child(path_index).unwrap(), state)?;
+
+            for i in [0, path_index] {
+                let child = arguments.child(i).unwrap();
+                if child.value().is_identifier() {
+                    return Err(Error::VariableName {
+                        name: child.text().to_string(),
+                        token: child.clone(),
+                    });
+                }
+            }
+
+            let format = arguments.child(path_index).unwrap().format();
+            token.set_format(format);
+        }
+
+        match functions::rule_call_expression(token, state) {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Stores a new user function, without executing its body
+#[inline(never)]
+fn handle_function_assignment(token: &mut Token, state: &mut ParserState) {
+    let name = token.children().first().unwrap().text();
+
+    // Block bodies are stored without their braces, as a script of several lines
+    let body = token.children().last().unwrap();
+    let definition = if body.rule() == Rule::function_body {
+        let text = body.text().trim();
+        let text = text.strip_prefix('{').unwrap_or(text);
+        text.strip_suffix('}').unwrap_or(text).trim()
+    } else {
+        body.text()
+    };
+
+    // Compile arguments
+    let mut arguments: Vec<String> = Vec::new();
+    for child in token.children().iter().skip(2) {
+        let s = child.text();
+        if s == "," {
+            continue;
+        }
+        if s == ")" {
+            break;
+        }
+        arguments.push(s.to_string());
+    }
+
+    // Store new function
+    state.user_functions.insert(
+        name.to_string(),
+        UserFunction::new(name.to_string(), arguments, definition.to_string()),
+    );
+
+    let def = token.children().last().unwrap().clone();
+    token.set_text(def.text());
+    token.set_value(Value::String(def.text().to_string()));
+}
+
+/// Reject any children left as unresolved identifiers
+#[inline(never)]
+fn check_identifiers(token: &Token) -> Result<(), Error> {
+    for child in token.children() {
+        if child.value().is_identifier() {
+            // Help function is allowed to have an unresolved identifier
+            if !(token.rule() == Rule::call_expression && token.child(0).unwrap().text() == "help")
+            {
+                return Err(Error::VariableName {
+                    name: child.text().to_string(),
+                    token: child.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns true if a call expression would resolve to the builtin if(), rather than
+/// a function overriding it
+fn is_builtin_if(token: &Token, state: &ParserState) -> bool {
+    if token.child(0).unwrap().text() != "if"
+        || !state.functions.has("if")
+        || state.user_functions.contains_key("if")
+    {
+        return false;
+    }
+
+    #[cfg(feature = "extensions")]
+    if state.extensions.has_function("if") {
+        return false;
+    }
+
+    true
+}
+
 type RuleHandler = fn(token: &mut Token, state: &mut ParserState) -> Option<Error>;
 fn handler_table() -> HashMap<Rule, RuleHandler> {
     HashMap::from([
@@ -188,7 +300,13 @@ fn rule_line(token: &mut Token, state: &mut ParserState) -> Option<Error> {
     };
 
     // Run specified decorator
-    match state.decorators.call(decorator_name, token, &token.value()) {
+    state.record_decorator_call(decorator_name);
+    match state.decorators.call_with_config(
+        decorator_name,
+        token,
+        &token.value(),
+        &state.display_config,
+    ) {
         Ok(s) => token.set_text(&s),
         Err(e) => {
             // Extension decorators
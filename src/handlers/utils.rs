@@ -1,8 +1,14 @@
 use crate::{token::Token, Error, ExpectedTypes, FloatType, IntegerType, Value};
 
+#[cfg(feature = "bigint")]
+use crate::BigIntType;
+
 pub type IntHandler = fn(l: IntegerType, r: IntegerType) -> Option<IntegerType>;
 pub type FloatHandler = fn(l: FloatType, r: FloatType) -> FloatType;
 
+#[cfg(feature = "bigint")]
+pub type BigIntHandler = fn(l: &BigIntType, r: &BigIntType) -> Option<BigIntType>;
+
 /// Perform an integer calculation against 2 values
 ///
 /// # Arguments
@@ -164,7 +170,34 @@ pub fn perform_float_calculation(
     }
 }
 
+/// Perform an arbitrary-precision integer calculation against 2 values
+/// Returns None unless one value is a bigint, and both are integers
+///
+/// # Arguments
+/// * `l` - Left value
+/// * `r` - Right value
+/// * `handler` - checked_* function
+#[cfg(feature = "bigint")]
+pub fn perform_bigint_calculation(
+    expression: &Token,
+    l: &Value,
+    r: &Value,
+    handler: BigIntHandler,
+) -> Option<Result<Value, Error>> {
+    if !l.is_bigint() && !r.is_bigint() {
+        return None;
+    }
+
+    let (lv, rv) = (l.as_bigint()?, r.as_bigint()?);
+    Some(match handler(&lv, &rv) {
+        Some(n) => Ok(Value::BigInt(n)),
+        None => Err(Error::Overflow(expression.clone())),
+    })
+}
+
 /// Perform a calculation against 2 values
+/// Arrays are operated on element-wise, and must be of equal lengths
+/// A scalar paired with an array, in either order, is applied to each element
 ///
 /// # Arguments
 /// * `l` - Left value
@@ -346,4 +379,55 @@ mod test_token {
             .unwrap()
         );
     }
+
+    #[test]
+    fn test_array_lengths() {
+        let mut state = ParserState::new();
+        for op in ["+", "-", "*", "/", "%", "**", "&", "|", "^", "<<", ">>"] {
+            let mismatched = format!("[1, 2] {} [1, 2, 3]", op);
+            assert!(
+                matches!(
+                    Token::new(&mismatched, &mut state),
+                    Err(Error::ArrayLengths(_))
+                ),
+                "{}",
+                mismatched
+            );
+
+            let equal = format!("[1, 2] {} [1, 2]", op);
+            assert!(Token::new(&equal, &mut state).is_ok(), "{}", equal);
+        }
+
+        assert!(matches!(
+            Token::new("[[1, 2], [3]] + [[1], [3]]", &mut state),
+            Err(Error::ArrayLengths(_))
+        ));
+    }
+
+    #[test]
+    fn test_scalar_broadcast() {
+        let mut state = ParserState::new();
+        let mut eval = |input: String| Token::new(&input, &mut state).unwrap().value();
+
+        for op in ["+", "-", "*", "/", "%", "**", "&", "|", "^", "<<", ">>"] {
+            assert_eq!(
+                Value::Array(vec![
+                    eval(format!("4 {} 2", op)),
+                    eval(format!("8 {} 2", op))
+                ]),
+                eval(format!("[4, 8] {} 2", op)),
+                "array {} scalar",
+                op
+            );
+            assert_eq!(
+                Value::Array(vec![
+                    eval(format!("2 {} 4", op)),
+                    eval(format!("2 {} 8", op))
+                ]),
+                eval(format!("2 {} [4, 8]", op)),
+                "scalar {} array",
+                op
+            );
+        }
+    }
 }
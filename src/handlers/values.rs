@@ -14,7 +14,7 @@ use crate::{
 /// * `input` - Source string
 /// * `prefix` - Number prefix to remove from the string
 /// * `base` - Numeric base
-fn parse_radix(
+pub fn parse_radix(
     input: &str,
     prefix: &[&str],
     base: u32,
@@ -42,7 +42,9 @@ pub fn handler_table() -> HashMap<Rule, RuleHandler> {
         (Rule::oct, rule_oct as RuleHandler),
         (Rule::bin, rule_bin as RuleHandler),
         (Rule::hex, rule_hex as RuleHandler),
+        (Rule::hex_float, rule_hex_float as RuleHandler),
         (Rule::index_expression, rule_index_expression as RuleHandler),
+        (Rule::member, rule_member as RuleHandler),
     ])
 }
 
@@ -60,16 +62,19 @@ fn rule_atomic_value(token: &mut Token, _state: &mut ParserState) -> Option<Erro
 
 /// Array value
 /// [5,2,'test']
-fn rule_array(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
+fn rule_array(token: &mut Token, state: &mut ParserState) -> Option<Error> {
     let child_container = token.child(1).unwrap().clone();
     if matches!(child_container.rule(), Rule::expression_list) {
+        let elements = child_container
+            .children()
+            .iter()
+            .filter(|e| !matches!(e.rule(), Rule::comma));
+        if let Err(e) = state.check_array_length(elements.clone().count(), token) {
+            return Some(e);
+        }
+
         token.set_value(Value::Array(
-            child_container
-                .children()
-                .iter()
-                .filter(|e| !matches!(e.rule(), Rule::comma))
-                .map(|e| e.value())
-                .collect::<Vec<Value>>(),
+            elements.map(|e| e.value()).collect::<Vec<Value>>(),
         ));
     } else if matches!(child_container.rule(), Rule::rbracket) {
         token.set_value(Value::Array(vec![]));
@@ -82,9 +87,19 @@ fn rule_array(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
 
 /// Object value
 /// ['test': 1, 3: 5]
-fn rule_object(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
+fn rule_object(token: &mut Token, state: &mut ParserState) -> Option<Error> {
     let child_container = token.child(1).unwrap().clone();
     if matches!(child_container.rule(), Rule::property_list) {
+        let properties = child_container
+            .children()
+            .iter()
+            .filter(|c| c.text() == ",")
+            .count()
+            + 1;
+        if let Err(e) = state.check_array_length(properties, token) {
+            return Some(e);
+        }
+
         let mut object = ObjectType::new();
         let mut buffer: Vec<Value> = vec![];
         for child in child_container.children() {
@@ -268,12 +283,65 @@ fn rule_hex(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
     None
 }
 
+/// Hexadecimal floating point value
+/// 0x1.8p3
+/// 0x1p-2
+fn rule_hex_float(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
+    match parse_hex_float(token.text()) {
+        Some(n) => token.set_value(Value::Float(n)),
+        None => {
+            return Some(Error::ValueParsing {
+                input: token.text().to_string(),
+                expected_type: ExpectedTypes::Float,
+                token: token.clone(),
+            });
+        }
+    }
+    None
+}
+
+/// Parse a hexadecimal float of the form 0x<mantissa>p<exponent>
+///
+/// # Arguments
+/// * `input` - Source string
+fn parse_hex_float(input: &str) -> Option<FloatType> {
+    let trimmed = input.get(2..)?;
+    let (mantissa, exponent) = trimmed.split_once(['p', 'P'])?;
+    let exponent = exponent.parse::<i32>().ok()?;
+
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+
+    let mut value: FloatType = 0.0;
+    for c in whole.chars() {
+        value = value * 16.0 + c.to_digit(16)? as FloatType;
+    }
+
+    let mut scale: FloatType = 1.0;
+    for c in fraction.chars() {
+        scale /= 16.0;
+        value += c.to_digit(16)? as FloatType * scale;
+    }
+
+    Some(value * FloatType::powi(2.0, exponent))
+}
+
+/// Object member name, used as a string key
+/// x.name
+fn rule_member(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
+    token.set_value(Value::String(token.text().to_string()));
+    None
+}
+
 /// indexing operator
 /// x[5]
+/// x.name
 fn rule_index_expression(token: &mut Token, _state: &mut ParserState) -> Option<Error> {
     let mut source = token.child(0).unwrap().value();
-    for child in token.children().iter().skip(2) {
-        if child.rule() == Rule::lbracket || child.rule() == Rule::rbracket {
+    for child in token.children().iter().skip(1) {
+        if matches!(child.rule(), Rule::lbracket | Rule::rbracket | Rule::dot) {
             continue;
         }
 
@@ -341,6 +409,34 @@ mod test_token {
         );
     }
 
+    #[test]
+    fn test_value_handler_hex_float() {
+        let mut state = ParserState::new();
+        assert_eq!(
+            Value::Float(12.0),
+            Token::new("0x1.8p3", &mut state).unwrap().value()
+        );
+        assert_eq!(
+            Value::Float(0.25),
+            Token::new("0x1p-2", &mut state).unwrap().value()
+        );
+        assert_eq!(
+            Value::Float(255.5),
+            Token::new("0XFF.8P0", &mut state).unwrap().value()
+        );
+        assert!(matches!(
+            Token::new("0x1.2.3p1", &mut state),
+            Err(Error::ValueParsing { .. })
+        ));
+
+        // Without exponent digits, p is a variable multiplying a hex integer
+        state.variables.insert("p".to_string(), Value::Integer(2));
+        assert_eq!(
+            Value::Integer(32),
+            Token::new("0x10p", &mut state).unwrap().value()
+        );
+    }
+
     #[test]
     fn test_value_handler_bin() {
         let mut state = ParserState::new();
@@ -570,4 +666,29 @@ mod test_token {
         assert_eq!(true, Token::new("array['test']", &mut state).is_err());
         assert_eq!(true, Token::new("array[3]", &mut state).is_err());
     }
+
+    #[test]
+    fn test_rule_index_expression_member() {
+        let mut state = ParserState::new();
+        Token::new("obj = {'name': 'test', 'inner': {'n': [5, 6]}}", &mut state).unwrap();
+        assert_eq!(
+            Token::new("obj['name']", &mut state).unwrap().value(),
+            Token::new("obj.name", &mut state).unwrap().value()
+        );
+        assert_eq!(
+            Value::Integer(6),
+            Token::new("obj.inner.n[1]", &mut state).unwrap().value()
+        );
+        assert!(matches!(
+            Token::new("obj.missing", &mut state),
+            Err(Error::Index { .. })
+        ));
+
+        // Numeric suffixes are still decimals
+        Token::new("x = 2", &mut state).unwrap();
+        assert_eq!(
+            Value::Float(1.0),
+            Token::new("x.5", &mut state).unwrap().value()
+        );
+    }
 }
@@ -80,6 +80,30 @@
 //! // Expressions being parsed can now call new_function(), and use the @new_decorator
 //! ```
 //!
+//! Decorators also receive the token being decorated, which reveals the format implied by its literals - whether it was written as a currency, for example:
+//! ```rust
+//! use lavendeux_parser::{ParserState, Error, Token, define_decorator, ExpectedTypes, OutputFormat};
+//!
+//! define_decorator!(
+//!     name = amount,
+//!     description = "Outputs dollar values with their unit, and other numbers as-is",
+//!     input = ExpectedTypes::IntOrFloat,
+//!     handler = |decorator, token, input| match token.source_format() {
+//!         OutputFormat::Dollars => Ok(format!("{} dollars", input.as_string())),
+//!         _ => Ok(input.as_string()),
+//!     }
+//! );
+//!
+//! fn main() -> Result<(), Error> {
+//!     let mut state : ParserState = ParserState::new();
+//!     state.decorators.register(amount);
+//!
+//!     assert_eq!("5 dollars", Token::new("$5 @amount", &mut state)?.text());
+//!     assert_eq!("5", Token::new("5 @amount", &mut state)?.text());
+//!     Ok(())
+//! }
+//! ```
+//!
 //! Javascript extensions give a flexible way of adding functionality at runtime.
 //! Extensions are run in a sandboxed environment, with no network or host access.  
 //! An extension must implement an extension() function taking no arguments and returning an object describing the extension - see example below
@@ -189,6 +213,7 @@
 //!
 //! // Strings are also supported
 //! concat("foo", "bar")
+//! "ab" * 3 // Strings can be repeated by multiplying them by an integer
 //!
 //! [1, 2, "test"] // Arrays can be composed of any combination of types
 //! [10, 12] + [1.2, 1.3] // Operations can be performed between arrays of the same size
@@ -234,6 +259,8 @@
 //! // You can also define functions
 //! f(x) = 2*x**2 + 3*x + 5
 //! f(2.3)
+//! // Functions can read variables set outside of them, but any assignments they make stay local
+//! g(x) = { y = x * 2; y + 1 } // A function body can also be a block of statements, returning the last
 //!
 //! // Functions work well with arrays
 //! sum(a) = element(a, 0) + ( len(a)>1 ? sum(dequeue(a)) : 0 )
@@ -258,7 +285,7 @@
 //! Operators
 //! =========
 //! Bitwise: AND (0xF & 0xA), OR (0xA | 0xF), XOR (0xA ^ 0xF), NOT (~0xA), SHIFT (0xF >> 1, 0xA << 1)
-//! Boolean: AND (true && false), OR (true || false), CMP (1 < 2, 4 >= 5), EQ (1 == 1, 2 != 5)
+//! Boolean: AND (true && false), OR (true || false), XOR (true ^^ false), CMP (1 < 2, 4 >= 5), EQ (1 == 1, 2 != 5)
 //! Arithmetic: Add/Sub (+, -), Mul/Div (*, /), Exponentiation (**), Modulo (%), Implied Mul ((5)(5), 5x)
 //! Unary: Factorial (5!!), Negation (-1, -(1+1))
 //!
@@ -415,7 +442,7 @@ pub use functions::{
 };
 
 mod decorators;
-pub use decorators::{DecoratorDefinition, DecoratorHandler};
+pub use decorators::{ConfigDecoratorHandler, DecoratorDefinition, DecoratorHandler};
 
 #[cfg(feature = "extensions")]
 mod extensions;
@@ -426,11 +453,18 @@ pub use extensions::Extension;
 /// Module defining errors that can occur during parsing
 pub use errors::Error;
 pub use state::ParserState;
+pub use token::OutputFormat;
 pub use token::Token;
 pub use value::ArrayType;
+#[cfg(feature = "bigint")]
+pub use value::BigIntType;
+#[cfg(feature = "bytes")]
+pub use value::BytesType;
+pub use value::DisplayConfig;
 pub use value::FloatType;
 pub use value::IntegerType;
 pub use value::Value;
+pub use value::WIRE_FORMAT_VERSION;
 
 #[cfg(test)]
 mod test_token {
@@ -1,15 +1,19 @@
 use crate::network::utils::*;
 use crate::value::Value;
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
 
 /// Represents an instance of an API
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ApiInstance {
     base_url: String,
     description: String,
     examples: String,
+
+    // Credentials are never written out with the rest of the API
+    #[serde(skip_serializing, default)]
     key: Option<String>,
 }
 
@@ -113,6 +117,31 @@ impl ApiInstance {
         let url = format!("{}/{}", self.base_url(), endpoint);
         request(&url, body, headers)
     }
+
+    /// Make a request to the API using a specific HTTP method
+    /// If the API has a key, it is sent as a bearer token unless an Authorization header is supplied
+    ///
+    /// # Arguments
+    /// * `method` - HTTP method, such as PUT
+    /// * `endpoint` - Endpoint to call
+    /// * `body` - Optional request body
+    /// * `headers` - Vec of extra headers to supply to the API
+    pub fn request_with_method(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        body: Option<String>,
+        mut headers: HashMap<String, String>,
+    ) -> Result<Value, reqwest::Error> {
+        if let Some(key) = self.key() {
+            headers
+                .entry("Authorization".to_string())
+                .or_insert_with(|| format!("Bearer {}", key));
+        }
+
+        let url = format!("{}/{}", self.base_url(), endpoint);
+        request_with_method(&url, method, body, headers)
+    }
 }
 
 impl fmt::Display for ApiInstance {
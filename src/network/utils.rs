@@ -20,6 +20,22 @@ pub fn resolve(hostname: &str) -> Result<Value, std::io::Error> {
     }
 }
 
+/// Find the hostname associated to an IP address with a reverse DNS lookup
+/// Returns None if the address has no PTR record
+///
+/// # Arguments
+/// * `address` - IP address to look up
+#[cfg(feature = "dns-functions")]
+pub fn reverse_resolve(address: &std::net::IpAddr) -> Result<Option<String>, std::io::Error> {
+    // Without a PTR record, the address is returned in its numeric form
+    let hostname = dns_lookup::lookup_addr(address)?;
+    Ok(if hostname == address.to_string() {
+        None
+    } else {
+        Some(hostname)
+    })
+}
+
 /// Fetch from a given URL
 ///
 /// # Arguments
@@ -30,16 +46,36 @@ pub fn request(
     url: &str,
     body: Option<String>,
     headers: HashMap<String, String>,
+) -> Result<Value, reqwest::Error> {
+    let method = match body {
+        None => reqwest::Method::GET,
+        Some(_) => reqwest::Method::POST,
+    };
+    request_with_method(url, method, body, headers)
+}
+
+/// Fetch from a given URL using a specific HTTP method
+///
+/// # Arguments
+/// * `url` - Target URL
+/// * `method` - HTTP method, such as PUT
+/// * `body` - Optional request body
+/// * `headers` - Array of header=value strings
+pub fn request_with_method(
+    url: &str,
+    method: reqwest::Method,
+    body: Option<String>,
+    headers: HashMap<String, String>,
 ) -> Result<Value, reqwest::Error> {
     match reqwest::blocking::Client::builder()
         .timeout(Duration::from_millis(1500))
         .build()
     {
         Ok(client) => {
-            let mut request = match body {
-                None => client.get(url),
-                Some(s) => client.post(url).body(s),
-            };
+            let mut request = client.request(method, url);
+            if let Some(s) = body {
+                request = request.body(s);
+            }
 
             for (header, value) in headers.iter() {
                 request = request.header(header, value);
@@ -1,8 +1,15 @@
-use super::value::Value;
+use super::token::Rule;
+use super::value::{DisplayConfig, Value};
+use super::{Error, Token};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
 use super::decorators;
 use super::functions;
+use super::help::Help;
 
 use super::network::ApiInstance;
 
@@ -11,6 +18,10 @@ use super::extensions;
 
 const MAX_STACK_DEPTH: usize = 50;
 
+/// A hook called before every function call, with the function's name and arguments
+/// Returning Some(result) replaces the call, and None lets the call proceed normally
+pub type CallHook = Arc<Mutex<dyn FnMut(&str, &[Value]) -> Option<Result<Value, Error>> + Send>>;
+
 /// Holds the properties of a function assigned inside an expression
 #[derive(Clone)]
 pub struct UserFunction {
@@ -65,6 +76,16 @@ impl UserFunction {
 #[derive(Clone)]
 pub struct ParserState {
     depth: usize,
+    max_depth: usize,
+    call_budget: Option<usize>,
+    call_count: Arc<AtomicUsize>,
+    step_limit: Option<usize>,
+    step_count: Arc<AtomicUsize>,
+    diagnostics: Option<Arc<Mutex<HashMap<String, usize>>>>,
+    max_array_length: Option<usize>,
+    max_string_length: Option<usize>,
+    rng: Option<Arc<Mutex<StdRng>>>,
+    read_only: bool,
 
     /// The assigned variables usable in expressions
     pub variables: HashMap<String, Value>,
@@ -84,6 +105,15 @@ pub struct ParserState {
     /// Available configured APIs
     pub apis: HashMap<String, ApiInstance>,
 
+    /// Options used to render results that have no decorator
+    pub display_config: DisplayConfig,
+
+    /// Named templates usable with the template() function
+    pub templates: HashMap<String, String>,
+
+    /// Optional hook consulted before each function call
+    pub on_call: Option<CallHook>,
+
     /// Currently loaded extensions
     #[cfg(feature = "extensions")]
     pub extensions: extensions::ExtensionTable,
@@ -100,6 +130,16 @@ impl ParserState {
     pub fn new() -> ParserState {
         ParserState {
             depth: 0,
+            max_depth: MAX_STACK_DEPTH,
+            call_budget: None,
+            call_count: Arc::new(AtomicUsize::new(0)),
+            step_limit: None,
+            step_count: Arc::new(AtomicUsize::new(0)),
+            diagnostics: None,
+            max_array_length: None,
+            max_string_length: None,
+            rng: None,
+            read_only: false,
             variables: HashMap::new(),
 
             constants: HashMap::from([
@@ -150,17 +190,355 @@ impl ParserState {
                 )),
             ]),
 
+            display_config: DisplayConfig::default(),
+            templates: HashMap::new(),
+            on_call: None,
+
             #[cfg(feature = "extensions")]
             extensions: extensions::ExtensionTable::new(),
         }
     }
 
+    /// Set a hook to be called before every function call
+    /// If the hook returns Some(result), that result is used instead of calling the function
+    ///
+    /// # Arguments
+    /// * `hook` - Closure taking the function name and arguments
+    pub fn set_on_call<F>(&mut self, hook: F)
+    where
+        F: FnMut(&str, &[Value]) -> Option<Result<Value, Error>> + Send + 'static,
+    {
+        self.on_call = Some(Arc::new(Mutex::new(hook)));
+    }
+
+    /// Define a constant value, which expressions can use but not overwrite
+    ///
+    /// # Arguments
+    /// * `name` - Constant name
+    /// * `value` - Constant value
+    pub fn set_constant(&mut self, name: &str, value: Value) {
+        self.variables.remove(name);
+        self.constants.insert(name.to_string(), value);
+    }
+
+    /// Evaluate an expression with a set of temporary variables
+    /// The session's variables are restored once evaluation completes
+    ///
+    /// # Arguments
+    /// * `input` - Expression to evaluate
+    /// * `vars` - Variable names and values to set during the evaluation
+    pub fn eval_with_vars(&mut self, input: &str, vars: &[(&str, Value)]) -> Result<Token, Error> {
+        let variables = self.variables.clone();
+        for (name, value) in vars {
+            self.variables.insert(name.to_string(), value.clone());
+        }
+
+        let result = Token::new(input, self);
+        self.variables = variables;
+        result
+    }
+
+    /// Evaluate an expression, and return the value of its last non-empty line
+    ///
+    /// # Arguments
+    /// * `input` - Expression to evaluate
+    pub fn eval(&mut self, input: &str) -> Result<Value, Error> {
+        let token = Token::new(input, self)?;
+        Ok(token
+            .children()
+            .iter()
+            .rev()
+            .find(|line| {
+                line.rule() == Rule::line && line.child(0).map(|c| c.rule()) != Some(Rule::eol)
+            })
+            .map(|line| line.value())
+            .unwrap_or(Value::None))
+    }
+
+    /// Evaluate an expression, and return the value of each of its lines
+    /// Empty lines are included, with a value of none
+    ///
+    /// # Arguments
+    /// * `input` - Expression to evaluate
+    pub fn eval_lines(&mut self, input: &str) -> Result<Vec<Value>, Error> {
+        let token = Token::new(input, self)?;
+        Ok(token
+            .children()
+            .iter()
+            .filter(|line| line.rule() == Rule::line)
+            .map(|line| line.value())
+            .collect())
+    }
+
+    /// Serialize the registered APIs to JSON, so that they can be restored with [ParserState::import_apis]
+    /// API keys are left out, and must be registered again after importing
+    pub fn export_apis(&self) -> String {
+        serde_json::to_string(&self.apis).unwrap_or_default()
+    }
+
+    /// Register the APIs from JSON produced by [ParserState::export_apis]
+    /// APIs with the same name as an existing one replace it
+    ///
+    /// # Arguments
+    /// * `input` - Exported APIs
+    pub fn import_apis(&mut self, input: &str) -> Result<(), serde_json::Error> {
+        let apis: HashMap<String, ApiInstance> = serde_json::from_str(input)?;
+        self.apis.extend(apis);
+        Ok(())
+    }
+
+    /// Returns help text for every builtin function grouped by category, followed by the
+    /// builtin decorators. Generated from the function and decorator tables
+    pub fn functions_help(&self) -> String {
+        let mut help = Help::new();
+        help.add_std_functions(self);
+        help.add_std_decorators(self);
+        help.to_string()
+    }
+
+    /// Register a named template for use with the template() function
+    /// Templates may contain ${key} placeholders, filled from an object
+    ///
+    /// # Arguments
+    /// * `name` - Template name
+    /// * `template` - Template string
+    pub fn register_template(&mut self, name: &str, template: &str) {
+        self.templates
+            .insert(name.to_string(), template.to_string());
+    }
+
+    /// Set the maximum number of function calls a single evaluation may make
+    /// None removes the limit
+    ///
+    /// # Arguments
+    /// * `budget` - Maximum number of function calls
+    pub fn set_call_budget(&mut self, budget: Option<usize>) {
+        self.call_budget = budget;
+    }
+
+    /// Returns the maximum number of function calls a single evaluation may make
+    pub fn call_budget(&self) -> Option<usize> {
+        self.call_budget
+    }
+
+    /// Reset the count of function calls made during the current evaluation
+    pub fn reset_call_count(&self) {
+        self.call_count.store(0, Ordering::Relaxed);
+    }
+
+    /// Record a function call, failing if the call budget has been exceeded
+    ///
+    /// # Arguments
+    /// * `token` - Token making the call
+    pub fn charge_call(&self, token: &Token) -> Result<(), Error> {
+        let calls = self.call_count.fetch_add(1, Ordering::Relaxed) + 1;
+        match self.call_budget {
+            Some(limit) if calls > limit => Err(Error::Budget {
+                limit,
+                token: token.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Set the maximum number of steps a single evaluation may take
+    /// None removes the limit
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of evaluation steps
+    pub fn set_step_limit(&mut self, limit: Option<usize>) {
+        self.step_limit = limit;
+    }
+
+    /// Returns the maximum number of steps a single evaluation may take
+    pub fn step_limit(&self) -> Option<usize> {
+        self.step_limit
+    }
+
+    /// Reset the count of steps taken during the current evaluation
+    pub fn reset_step_count(&self) {
+        self.step_count.store(0, Ordering::Relaxed);
+    }
+
+    /// Record an evaluation step, failing if the step limit has been exceeded
+    ///
+    /// # Arguments
+    /// * `token` - Token being evaluated
+    pub fn charge_step(&self, token: &Token) -> Result<(), Error> {
+        let steps = self.step_count.fetch_add(1, Ordering::Relaxed) + 1;
+        match self.step_limit {
+            Some(limit) if steps > limit => Err(Error::Timeout {
+                limit,
+                token: token.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Enable or disable counting of function and decorator calls
+    /// Counts are reset at the start of each evaluation
+    ///
+    /// # Arguments
+    /// * `enabled` - True to record calls
+    pub fn set_diagnostics(&mut self, enabled: bool) {
+        self.diagnostics = if enabled {
+            Some(Arc::new(Mutex::new(HashMap::new())))
+        } else {
+            None
+        };
+    }
+
+    /// Returns the number of times each function, and each decorator (prefixed with @),
+    /// was called during the last evaluation. Empty unless diagnostics are enabled
+    pub fn diagnostics(&self) -> HashMap<String, usize> {
+        match &self.diagnostics {
+            Some(counts) => counts.lock().map(|c| c.clone()).unwrap_or_default(),
+            None => HashMap::new(),
+        }
+    }
+
+    /// Clear the recorded call counts
+    pub fn reset_diagnostics(&self) {
+        if let Some(Ok(mut counts)) = self.diagnostics.as_ref().map(|c| c.lock()) {
+            counts.clear();
+        }
+    }
+
+    /// Record a call to a function or decorator, if diagnostics are enabled
+    ///
+    /// # Arguments
+    /// * `name` - Name of the function or decorator called
+    pub fn record_call(&self, name: &str) {
+        if let Some(Ok(mut counts)) = self.diagnostics.as_ref().map(|c| c.lock()) {
+            *counts.entry(name.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    /// Record a call to a decorator, under its name prefixed with @, if diagnostics are enabled
+    ///
+    /// # Arguments
+    /// * `name` - Name of the decorator called
+    pub fn record_decorator_call(&self, name: &str) {
+        if let Some(Ok(mut counts)) = self.diagnostics.as_ref().map(|c| c.lock()) {
+            *counts.entry(format!("@{}", name)).or_insert(0) += 1;
+        }
+    }
+
+    /// Enable or disable read-only mode
+    /// While read-only, variable and function assignments, and builtins that modify
+    /// the state, result in an error
+    ///
+    /// # Arguments
+    /// * `enabled` - True to reject assignments
+    pub fn set_read_only(&mut self, enabled: bool) {
+        self.read_only = enabled;
+    }
+
+    /// Returns true if assignments are currently rejected
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Fails if read-only mode is enabled
+    ///
+    /// # Arguments
+    /// * `token` - Token attempting to modify the state
+    pub fn check_writable(&self, token: &Token) -> Result<(), Error> {
+        if self.read_only {
+            Err(Error::ReadOnly(token.clone()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Set the maximum number of elements an expression may construct in an array or object
+    /// None removes the limit
+    ///
+    /// # Arguments
+    /// * `length` - Maximum number of elements
+    pub fn set_max_array_length(&mut self, length: Option<usize>) {
+        self.max_array_length = length;
+    }
+
+    /// Set the maximum length of a string an expression may construct
+    /// None removes the limit
+    ///
+    /// # Arguments
+    /// * `length` - Maximum string length
+    pub fn set_max_string_length(&mut self, length: Option<usize>) {
+        self.max_string_length = length;
+    }
+
+    /// Fails if an array of the given length would exceed the configured limit
+    ///
+    /// # Arguments
+    /// * `length` - Number of elements
+    /// * `token` - Token constructing the array
+    pub fn check_array_length(&self, length: usize, token: &Token) -> Result<(), Error> {
+        match self.max_array_length {
+            Some(max) if length > max => Err(Error::Overflow(token.clone())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails if a string of the given length would exceed the configured limit
+    ///
+    /// # Arguments
+    /// * `length` - String length
+    /// * `token` - Token constructing the string
+    pub fn check_string_length(&self, length: usize, token: &Token) -> Result<(), Error> {
+        match self.max_string_length {
+            Some(max) if length > max => Err(Error::Overflow(token.clone())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails if a value is larger than the configured array or string limits
+    ///
+    /// # Arguments
+    /// * `value` - Value to check
+    /// * `token` - Token that produced the value
+    pub fn check_value_size(&self, value: &Value, token: &Token) -> Result<(), Error> {
+        match value {
+            Value::String(s) => self.check_string_length(s.len(), token),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(b) => self.check_string_length(b.len(), token),
+            Value::Array(v) => self.check_array_length(v.len(), token),
+            Value::Object(v) => self.check_array_length(v.len(), token),
+            _ => Ok(()),
+        }
+    }
+
+    /// Seed the generator used by random functions, making their output reproducible
+    /// None restores the default, unseeded generator
+    ///
+    /// # Arguments
+    /// * `seed` - Seed for the generator
+    pub fn set_rng_seed(&mut self, seed: Option<u64>) {
+        self.rng = seed.map(|seed| Arc::new(Mutex::new(StdRng::seed_from_u64(seed))));
+    }
+
+    /// Run a callback with the generator random functions should use;
+    /// the seeded generator if one was set, or the thread's generator otherwise
+    ///
+    /// # Arguments
+    /// * `f` - Callback receiving the generator
+    pub fn with_rng<T>(&self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        match self.rng.as_ref().map(|rng| rng.lock()) {
+            Some(Ok(mut rng)) => f(&mut *rng),
+            _ => f(&mut rand::thread_rng()),
+        }
+    }
+
     /// Returns a new parser with the same properties, and the depth incremented
     /// Fails if the maximum depth is overshot
+    ///
+    /// The function, decorator and extension tables are shared with the new parser rather than
+    /// copied, so this is cheap enough to call once per user function call
     pub fn spawn_inner(&self) -> Option<ParserState> {
         let mut s = self.clone();
         s.depth = self.depth + 1;
-        if s.depth < MAX_STACK_DEPTH {
+        if s.depth < self.max_depth {
             Some(s)
         } else {
             None
@@ -171,4 +549,285 @@ impl ParserState {
     pub fn depth(&self) -> usize {
         self.depth
     }
+
+    /// Set the maximum depth recursive user functions may reach
+    ///
+    /// # Arguments
+    /// * `max_depth` - Maximum stack depth
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+
+    /// Returns the maximum depth recursive user functions may reach
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+}
+
+#[cfg(test)]
+mod test_parser_state {
+    use super::*;
+    use crate::test::*;
+    use crate::Token;
+
+    #[test]
+    fn test_set_constant() {
+        let mut state = ParserState::new();
+        state.set_constant("g", Value::Float(9.81));
+
+        assert_token_value_stateful!("g * 2", Value::Float(19.62), &mut state);
+        assert_token_error_stateful!("g = 5", ConstantValue, &mut state);
+        assert_eq!(Value::Float(9.81), state.constants["g"]);
+    }
+
+    #[test]
+    fn test_eval_with_vars() {
+        let mut state = ParserState::new();
+        Token::new("x = 2", &mut state).unwrap();
+
+        let token = state
+            .eval_with_vars("y = x * 3", &[("x", Value::Integer(10))])
+            .unwrap();
+        assert_eq!(Value::Integer(30), token.value());
+        assert_eq!(Value::Integer(2), state.variables["x"]);
+        assert_eq!(false, state.variables.contains_key("y"));
+    }
+
+    #[test]
+    fn test_eval() {
+        let mut state = ParserState::new();
+        assert_eq!(Value::Integer(4), state.eval("2 + 2").unwrap());
+        assert_eq!(
+            Value::Integer(10),
+            state.eval("x = 3\ny = x + 2\n\ny * 2\n").unwrap()
+        );
+        assert_eq!(Value::Integer(5), state.variables["y"]);
+        assert_eq!(Value::None, state.eval("").unwrap());
+        assert!(matches!(state.eval("1 / 0"), Err(Error::DivideByZero(_))));
+    }
+
+    #[test]
+    fn test_eval_lines() {
+        let mut state = ParserState::new();
+        assert_eq!(
+            vec![Value::Integer(3), Value::Integer(5), Value::Integer(15)],
+            state.eval_lines("x = 3\ny = x + 2\nx * y").unwrap()
+        );
+        assert_eq!(
+            vec![Value::Integer(1), Value::None, Value::Integer(2)],
+            state.eval_lines("1\n\n2\n").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_read_only() {
+        let mut state = ParserState::new();
+        state.set_read_only(true);
+
+        assert_token_value_stateful!("5 + 5", Value::Integer(10), &mut state);
+        assert_token_error_stateful!("x = 5", ReadOnly, &mut state);
+        assert_token_error_stateful!("f(x) = x + 1", ReadOnly, &mut state);
+        assert!(!state.variables.contains_key("x"));
+        assert!(!state.user_functions.contains_key("f"));
+
+        // Builtins that modify the state are rejected too
+        assert_token_error_stateful!(
+            "api_register('foo', 'https://example.com')",
+            ReadOnly,
+            &mut state
+        );
+        assert_token_error_stateful!("api_delete('foo')", ReadOnly, &mut state);
+        assert!(!state.apis.contains_key("foo"));
+
+        state
+            .variables
+            .insert("a".to_string(), Value::Array(vec![Value::Integer(1)]));
+        assert_token_error_stateful!("pop(a)", ReadOnly, &mut state);
+        assert_eq!(Value::Array(vec![Value::Integer(1)]), state.variables["a"]);
+        assert_token_value_stateful!("pop([1, 2])", Value::Integer(2), &mut state);
+
+        state.set_read_only(false);
+        assert_token_value_stateful!("x = 5", Value::Integer(5), &mut state);
+    }
+
+    #[test]
+    fn test_functions_help() {
+        let state = ParserState::new();
+        let help = state.functions_help();
+
+        let math = help.split("Math Functions").nth(1).unwrap();
+        let math = math.split("\n\n").next().unwrap();
+        assert!(math.contains("sqrt("));
+        assert!(!math.contains("concat("));
+
+        assert!(help.contains("Built-in Decorators"));
+        assert!(help.contains("@hex"));
+    }
+
+    #[test]
+    fn test_export_apis() {
+        let mut state = ParserState::new();
+        Token::new(
+            "api_register('mock', 'http://localhost:8080/', 'secret')",
+            &mut state,
+        )
+        .unwrap();
+        let exported = state.export_apis();
+        assert!(!exported.contains("secret"));
+
+        let mut restored = ParserState::new();
+        restored.apis.clear();
+        restored.import_apis(&exported).unwrap();
+
+        let list = restored.eval("api_list()").unwrap().as_string();
+        assert!(list.contains("mock: http://localhost:8080"));
+        assert_eq!(&None, restored.apis["mock"].key());
+        assert_eq!(state.apis.len(), restored.apis.len());
+
+        assert!(restored.import_apis("not json").is_err());
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let mut state = ParserState::new();
+        assert_token_value_stateful!("sqrt(4)", Value::Float(2.0), &mut state);
+        assert_eq!(true, state.diagnostics().is_empty());
+
+        state.set_diagnostics(true);
+        assert_token_value_stateful!(
+            "sqrt(4) + sqrt(9) + sqrt(16) @float",
+            Value::Float(9.0),
+            &mut state
+        );
+        let counts = state.diagnostics();
+        assert_eq!(3, counts["sqrt"]);
+        assert_eq!(1, counts["@float"]);
+
+        assert_token_value_stateful!("sqrt(4)", Value::Float(2.0), &mut state);
+        assert_eq!(1, state.diagnostics()["sqrt"]);
+    }
+
+    #[test]
+    fn test_display_config() {
+        let mut state = ParserState::new();
+        state.display_config.float_precision = 2;
+        state.display_config.quote_strings = true;
+
+        assert_token_text_stateful!("[1.0/3, 'a']", "[0.33, \"a\"]", &mut state);
+        assert_token_text_stateful!("1.0/3 @float", "0.33", &mut state);
+        assert_token_text_stateful!("1.0/3 @default", "0.33", &mut state);
+        assert_token_text_stateful!("1.0/3 @array", "[0.33]", &mut state);
+        assert_token_text_stateful!("['a'] @array", "[\"a\"]", &mut state);
+        assert_token_text_stateful!("[1.0/3, 2] @float", "[0.33, 2.0]", &mut state);
+
+        // Decorators registered without a config-aware handler ignore the display options
+        state.decorators.register(crate::DecoratorDefinition {
+            name: &["float"],
+            description: "",
+            argument: crate::ExpectedTypes::Any,
+            handler: |_, _, input| Ok(input.as_string()),
+        });
+        assert_token_text_stateful!("1.0/3 @float", "0.33333333", &mut state);
+    }
+
+    #[test]
+    fn test_call_budget() {
+        let mut state = ParserState::new();
+        Token::new("f(x) = x == 0 ? 0 : f(x - 1)", &mut state).unwrap();
+
+        state.set_call_budget(Some(10));
+        assert_token_value_stateful!("f(5)", Value::Integer(0), &mut state);
+        assert_token_error_stateful!("f(20)", Budget, &mut state);
+
+        state.set_call_budget(None);
+        assert_token_value_stateful!("f(20)", Value::Integer(0), &mut state);
+    }
+
+    #[test]
+    fn test_spawn_inner() {
+        let mut state = ParserState::new();
+        Token::new("x = 5\nf(n) = n == 0 ? x : f(n - 1)", &mut state).unwrap();
+
+        let mut inner = state.spawn_inner().unwrap();
+        assert_eq!(state.depth() + 1, inner.depth());
+        assert_eq!(Value::Integer(5), inner.variables["x"]);
+        inner.functions.remove("sqrt");
+        assert!(state.functions.has("sqrt"));
+
+        #[cfg(feature = "extensions")]
+        {
+            state
+                .extensions
+                .load("example_extensions/simple_extension.js")
+                .unwrap();
+            let mut inner = state.spawn_inner().unwrap();
+            assert!(inner.extensions.has_function("add"));
+            inner
+                .extensions
+                .remove("example_extensions/simple_extension.js");
+            assert!(state.extensions.has_function("add"));
+            assert!(!inner.extensions.has_function("add"));
+        }
+
+        assert_token_value_stateful!("f(40)", Value::Integer(5), &mut state);
+    }
+
+    #[test]
+    fn test_max_depth() {
+        let mut state = ParserState::new();
+        assert_eq!(MAX_STACK_DEPTH, state.max_depth());
+        Token::new("f(x) = x == 0 ? 0 : f(x - 1)", &mut state).unwrap();
+
+        state.set_max_depth(10);
+        assert_token_error_stateful!("f(20)", StackOverflow, &mut state);
+
+        state.set_max_depth(30);
+        assert_token_value_stateful!("f(20)", Value::Integer(0), &mut state);
+    }
+
+    #[test]
+    fn test_max_sizes() {
+        let mut state = ParserState::new();
+        state.set_max_string_length(Some(5));
+        state.set_max_array_length(Some(3));
+
+        assert_token_value_stateful!("'ab' + 'cd'", Value::from("abcd"), &mut state);
+        assert_token_error_stateful!("'abc' + 'def'", Overflow, &mut state);
+        assert_token_error_stateful!("concat('abc', 'def')", Overflow, &mut state);
+
+        assert_token_value_stateful!(
+            "push([1, 2], 3)",
+            Value::Array(vec![
+                Value::Integer(1),
+                Value::Integer(2),
+                Value::Integer(3)
+            ]),
+            &mut state
+        );
+        assert_token_error_stateful!("push([1, 2, 3], 4)", Overflow, &mut state);
+        assert_token_error_stateful!("merge([1, 2], [3, 4])", Overflow, &mut state);
+        assert_token_error_stateful!("to_hex('abc')", Overflow, &mut state);
+
+        // Literals are checked before their elements are collected
+        assert_token_error_stateful!("[1, 2, 3, 4]", Overflow, &mut state);
+        assert_token_error_stateful!("{'a': 1, 'b': 2, 'c': 3, 'd': 4}", Overflow, &mut state);
+        assert_token_value_stateful!(
+            "{'a': 1}",
+            Value::Object(HashMap::from([(Value::from("a"), Value::Integer(1))])),
+            &mut state
+        );
+    }
+
+    #[test]
+    fn test_step_limit() {
+        let mut state = ParserState::new();
+        Token::new("f(x) = x == 0 ? 0 : f(x - 1)", &mut state).unwrap();
+
+        state.set_step_limit(Some(200));
+        assert_token_value_stateful!("1 + 2", Value::Integer(3), &mut state);
+        assert_token_error_stateful!("f(40)", Timeout, &mut state);
+
+        state.set_step_limit(None);
+        assert_token_value_stateful!("f(40)", Value::Integer(0), &mut state);
+    }
 }
@@ -11,13 +11,26 @@ use pest_derive::Parser;
 #[grammar = "grammar.pest"]
 struct LavendeuxParser;
 
+/// The format a token's value should be output in, as implied by the literals it was computed from
+/// Formats from higher tiers take precedence when operands are combined, so `$5 + 1` is in dollars
 #[derive(Copy, Clone, Debug)]
 pub enum OutputFormat {
+    /// Format not yet determined
     Unknown = 0,
+
+    /// Plain, type-dependent output
     Default = 10,
+
+    /// Value was written in dollars
     Dollars = 20,
+
+    /// Value was written in euros
     Euros = 21,
+
+    /// Value was written in pounds
     Pounds = 22,
+
+    /// Value was written in yen
     Yen = 23,
 }
 
@@ -99,6 +112,11 @@ impl Token {
     /// * `input` - Source string
     /// * `state` - The current parser state
     pub fn new(input: &str, state: &mut ParserState) -> Result<Token, Error> {
+        if state.depth() == 0 {
+            state.reset_call_count();
+            state.reset_step_count();
+            state.reset_diagnostics();
+        }
         Self::parse(input, crate::handlers::Handler::default(), state)
     }
 
@@ -217,6 +235,16 @@ impl Token {
         self.format
     }
 
+    /// Return the format implied by the literals the token's value was computed from
+    /// Decorators receive the token of the line being decorated, and can use this to
+    /// tell a value like `$5` apart from a plain `5`
+    pub fn source_format(&self) -> OutputFormat {
+        match self.format {
+            OutputFormat::Unknown => OutputFormat::Default,
+            f => f,
+        }
+    }
+
     /// Get the nth child token as a mutable reference, if possible
     pub fn mut_child(&mut self, n: usize) -> Option<&mut Token> {
         if n < self.children.len() {
@@ -287,6 +315,23 @@ mod test_token {
         );
     }
 
+    #[test]
+    fn test_source_format() {
+        let mut state: ParserState = ParserState::new();
+        let format = |input: &str, state: &mut ParserState| {
+            Token::new(input, state).unwrap().source_format()
+        };
+
+        assert!(matches!(format("5", &mut state), OutputFormat::Default));
+        assert!(matches!(format("'a'", &mut state), OutputFormat::Default));
+        assert!(matches!(format("$5", &mut state), OutputFormat::Dollars));
+        assert!(matches!(format("2 * 5€", &mut state), OutputFormat::Euros));
+        assert!(matches!(
+            format("$5 @hex", &mut state),
+            OutputFormat::Dollars
+        ));
+    }
+
     #[test]
     fn test_grammar_atomic_value() {
         let mut state: ParserState = ParserState::new();
@@ -420,8 +465,8 @@ mod test_token {
         assert_token_value!("-~3!!", Value::Integer(-303));
 
         // Overflows and errors
-        assert_token_error!("1/0", Overflow);
-        assert_token_error!("5+5\n 1/0", Overflow);
+        assert_token_error!("1/0", DivideByZero);
+        assert_token_error!("5+5\n 1/0", DivideByZero);
         assert_token_error!("99999999999999999999999999999999999999999", ValueParsing);
         assert_token_error!("1+99999999999999999999999999999999999999999", ValueParsing);
         assert_token_error!("999999999999999999*999999999999999999", Overflow);
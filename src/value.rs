@@ -1,3 +1,4 @@
+use crate::{Error, ExpectedTypes, Token};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::cmp::Ordering;
 use std::collections::HashMap;
@@ -10,6 +11,14 @@ pub type IntegerType = i64;
 /// The datatype for floating point values
 pub type FloatType = f64;
 
+/// The datatype for arbitrary-precision integer values
+#[cfg(feature = "bigint")]
+pub type BigIntType = num_bigint::BigInt;
+
+/// The datatype for binary values
+#[cfg(feature = "bytes")]
+pub type BytesType = Vec<u8>;
+
 /// The datatype for array values
 pub type ArrayType = Vec<Value>;
 
@@ -42,6 +51,14 @@ pub enum Value {
     /// A floating point value - integers can also be expressed as floats
     Float(FloatType),
 
+    /// An arbitrary-precision integer value
+    #[cfg(feature = "bigint")]
+    BigInt(BigIntType),
+
+    /// A sequence of raw bytes
+    #[cfg(feature = "bytes")]
+    Bytes(BytesType),
+
     /// A string value - all types can be expressed as strings
     String(String),
 
@@ -68,6 +85,10 @@ impl<'de> Deserialize<'de> for Value {
             String(String),
             Array(ArrayType),
             Object(Vec<(Value, Value)>),
+            #[cfg(feature = "bigint")]
+            BigInt(String),
+            #[cfg(feature = "bytes")]
+            Bytes(BytesType),
         }
 
         let _value = IntermediateValue::deserialize(deserializer)?;
@@ -83,6 +104,13 @@ impl<'de> Deserialize<'de> for Value {
                 let m: ObjectType = o.into_iter().collect();
                 Ok(Value::Object(m))
             }
+            #[cfg(feature = "bigint")]
+            IntermediateValue::BigInt(s) => s
+                .parse::<BigIntType>()
+                .map(Value::BigInt)
+                .map_err(serde::de::Error::custom),
+            #[cfg(feature = "bytes")]
+            IntermediateValue::Bytes(b) => Ok(Value::Bytes(b)),
         }
     }
 }
@@ -106,10 +134,136 @@ impl Serialize for Value {
                 let flat: Vec<(&Value, &Value)> = o.iter().map(|(item, idx)| (item, idx)).collect();
                 serializer.serialize_newtype_variant("Value", 7, "Object", &flat)
             }
+            #[cfg(feature = "bigint")]
+            Value::BigInt(n) => {
+                serializer.serialize_newtype_variant("Value", 8, "BigInt", &n.to_string())
+            }
+            #[cfg(feature = "bytes")]
+            Value::Bytes(b) => serializer.serialize_newtype_variant("Value", 9, "Bytes", b),
+        }
+    }
+}
+
+/// Version of the wire format produced by [Value::to_wire]
+pub const WIRE_FORMAT_VERSION: u32 = 1;
+
+/// Wire representation of a value, for exchange with hosts in other languages
+/// Each value is an object with a lowercase `type` tag and, except for none, a `value`:
+/// `{"type": "integer", "value": 5}`, `{"type": "array", "value": [...]}`
+/// Objects are a list of `[key, value]` pairs, since keys need not be strings
+/// Non-finite floats, which JSON cannot represent, are the strings "NaN", "inf" and "-inf"
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+enum WireValue {
+    None,
+    Identifier(String),
+    Boolean(bool),
+    Integer(IntegerType),
+    #[serde(with = "wire_float")]
+    Float(FloatType),
+    String(String),
+    Array(Vec<WireValue>),
+    Object(Vec<(WireValue, WireValue)>),
+    #[cfg(feature = "bigint")]
+    BigInt(String),
+    #[cfg(feature = "bytes")]
+    Bytes(BytesType),
+}
+
+/// Wire representation of floats, spelling out the non-finite values
+mod wire_float {
+    use super::FloatType;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(n: &FloatType, serializer: S) -> Result<S::Ok, S::Error> {
+        if n.is_nan() {
+            serializer.serialize_str("NaN")
+        } else if n.is_infinite() {
+            serializer.serialize_str(if *n > 0.0 { "inf" } else { "-inf" })
+        } else {
+            serializer.serialize_f64(*n)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FloatType, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum WireFloat {
+            Finite(FloatType),
+            NonFinite(String),
+        }
+
+        match WireFloat::deserialize(deserializer)? {
+            WireFloat::Finite(n) => Ok(n),
+            WireFloat::NonFinite(s) => match s.as_str() {
+                "NaN" => Ok(FloatType::NAN),
+                "inf" => Ok(FloatType::INFINITY),
+                "-inf" => Ok(FloatType::NEG_INFINITY),
+                _ => Err(D::Error::custom("invalid float")),
+            },
+        }
+    }
+}
+
+/// Versioned envelope around a wire value
+#[derive(Serialize, Deserialize)]
+struct WireEnvelope {
+    version: u32,
+    value: WireValue,
+}
+
+impl From<&Value> for WireValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::None => WireValue::None,
+            Value::Identifier(s) => WireValue::Identifier(s.clone()),
+            Value::Boolean(b) => WireValue::Boolean(*b),
+            Value::Integer(n) => WireValue::Integer(*n),
+            Value::Float(n) => WireValue::Float(*n),
+            Value::String(s) => WireValue::String(s.clone()),
+            Value::Array(v) => WireValue::Array(v.iter().map(WireValue::from).collect()),
+            Value::Object(v) => WireValue::Object(
+                v.iter()
+                    .map(|(k, e)| (WireValue::from(k), WireValue::from(e)))
+                    .collect(),
+            ),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(n) => WireValue::BigInt(n.to_string()),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(b) => WireValue::Bytes(b.clone()),
         }
     }
 }
 
+/// Fails if the wire value holds data that cannot be represented, such as a malformed bigint
+impl TryFrom<WireValue> for Value {
+    type Error = ();
+    fn try_from(value: WireValue) -> Result<Self, Self::Error> {
+        Ok(match value {
+            WireValue::None => Value::None,
+            WireValue::Identifier(s) => Value::Identifier(s),
+            WireValue::Boolean(b) => Value::Boolean(b),
+            WireValue::Integer(n) => Value::Integer(n),
+            WireValue::Float(n) => Value::Float(n),
+            WireValue::String(s) => Value::String(s),
+            WireValue::Array(v) => Value::Array(
+                v.into_iter()
+                    .map(Value::try_from)
+                    .collect::<Result<ArrayType, ()>>()?,
+            ),
+            WireValue::Object(v) => Value::Object(
+                v.into_iter()
+                    .map(|(k, e)| Ok((Value::try_from(k)?, Value::try_from(e)?)))
+                    .collect::<Result<ObjectType, ()>>()?,
+            ),
+            #[cfg(feature = "bigint")]
+            WireValue::BigInt(s) => Value::BigInt(s.parse().map_err(|_| ())?),
+            #[cfg(feature = "bytes")]
+            WireValue::Bytes(b) => Value::Bytes(b),
+        })
+    }
+}
+
 impl std::hash::Hash for Value {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         core::mem::discriminant(self).hash(state);
@@ -119,6 +273,10 @@ impl std::hash::Hash for Value {
             Value::Boolean(b) => b.hash(state),
             Value::Integer(i) => i.hash(state),
             Value::Float(f) => f.to_bits().hash(state),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(n) => n.hash(state),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(b) => b.hash(state),
             Value::String(s) => s.hash(state),
             Value::Array(a) => a.hash(state),
             Value::Object(o) => {
@@ -130,6 +288,66 @@ impl std::hash::Hash for Value {
     }
 }
 
+/// Options controlling how values are rendered as strings
+#[derive(Clone, Debug)]
+pub struct DisplayConfig {
+    /// Maximum number of decimal places shown for floating point values
+    pub float_precision: i32,
+
+    /// Show object keys in sorted order
+    pub sort_keys: bool,
+
+    /// Surround strings inside arrays with quotes
+    pub quote_strings: bool,
+
+    /// Truncate output longer than this many characters
+    pub max_length: Option<usize>,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            float_precision: MAX_FLOAT_PRECISION,
+            sort_keys: false,
+            quote_strings: false,
+            max_length: None,
+        }
+    }
+}
+
+/// Surround a string with double quotes, escaping special characters
+fn quote_string(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\")
+            .replace('\'', "\\'")
+            .replace('\"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('\r', "\\r")
+            .replace('\t', "\\t")
+    )
+}
+
+/// Render bytes as a lowercase hexadecimal string
+#[cfg(feature = "bytes")]
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Render a float in exponential notation, with at most the given number of decimal places
+/// 0.000000000125 -> 1.25e-10
+fn exponential_string(n: FloatType, precision: usize) -> String {
+    let s = format!("{:.*e}", precision, n);
+    match s.split_once('e') {
+        Some((mantissa, exponent)) if mantissa.contains('.') => format!(
+            "{}e{}",
+            mantissa.trim_end_matches('0').trim_end_matches('.'),
+            exponent
+        ),
+        _ => s,
+    }
+}
+
 impl std::fmt::Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}", self.as_string())
@@ -139,13 +357,84 @@ impl std::fmt::Display for Value {
 impl Value {
     /// Return the value as a string
     pub fn as_string(&self) -> String {
+        self.display(&DisplayConfig::default())
+    }
+
+    /// Render the value as an expression that parses back into an equal value
+    /// Strings are always quoted and escaped, and floats keep their full precision
+    /// Non-finite floats cannot be represented, and render as they would normally
+    pub fn to_parseable_string(&self) -> String {
+        match self {
+            Value::String(s) => quote_string(s),
+            Value::Float(n) if n.is_finite() => format!("{:?}", n),
+            Value::Array(v) => format!(
+                "[{}]",
+                v.iter()
+                    .map(|e| e.to_parseable_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            Value::Object(v) => {
+                let mut keys: Vec<&Value> = v.keys().collect();
+                keys.sort();
+                format!(
+                    "{{{}}}",
+                    keys.iter()
+                        .map(|k| format!(
+                            "{}: {}",
+                            k.to_parseable_string(),
+                            v[*k].to_parseable_string()
+                        ))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            }
+            #[cfg(feature = "bigint")]
+            Value::BigInt(n) => format!("bigint(\"{}\")", n),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(b) => format!(
+                "to_bytes([{}])",
+                b.iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            _ => self.as_string(),
+        }
+    }
+
+    /// Render the value as a string, according to the given display options
+    ///
+    /// # Arguments
+    /// * `config` - Display options
+    pub fn display(&self, config: &DisplayConfig) -> String {
+        let output = self.display_inner(config, false);
+        match config.max_length {
+            Some(max) if output.chars().count() > max => {
+                format!("{}...", output.chars().take(max).collect::<String>())
+            }
+            _ => output,
+        }
+    }
+
+    /// Render the value as a string, noting whether it is nested in an array or object
+    fn display_inner(&self, config: &DisplayConfig, nested: bool) -> String {
         match self {
             Value::Boolean(v) => (if *v { "true" } else { "false" }).to_string(),
             Value::Integer(n) => {
                 format!("{}", *n)
             }
             Value::Float(n) => {
-                let multiplier = f64::powi(10.0, MAX_FLOAT_PRECISION);
+                // Magnitudes that would round away to nothing fall back to exponential notation
+                let magnitude = n.abs();
+                if n.is_finite()
+                    && magnitude != 0.0
+                    && magnitude < f64::powi(10.0, -config.float_precision)
+                {
+                    return exponential_string(*n, config.float_precision.max(0) as usize);
+                }
+
+                let multiplier = f64::powi(10.0, config.float_precision);
                 let mut v = (*n * multiplier).round() / multiplier;
 
                 if v == -0.0 {
@@ -158,53 +447,43 @@ impl Value {
 
                 f
             }
+            Value::String(s) if nested && config.quote_strings => quote_string(s),
             Value::String(s) => s.to_string(),
             Value::Array(v) => format!(
                 "[{}]",
                 v.iter()
-                    .map(|e| e.as_string())
-                    .collect::<Vec<String>>()
-                    .join(", ")
-            ),
-            Value::Object(v) => format!(
-                "{{{}}}",
-                v.keys()
-                    .map(|k| format!(
-                        "{}:{}",
-                        if k.is_string() {
-                            format!(
-                                "\"{}\"",
-                                k.as_string()
-                                    .replace('\'', "\\'")
-                                    .replace('\"', "\\\"")
-                                    .replace('\n', "\\n")
-                                    .replace('\r', "\\r")
-                                    .replace('\t', "\\t")
-                            )
-                        } else {
-                            k.to_string()
-                        },
-                        if v.get(k).unwrap().is_string() {
-                            format!(
-                                "\"{}\"",
-                                v.get(k)
-                                    .unwrap()
-                                    .as_string()
-                                    .replace('\'', "\\'")
-                                    .replace('\"', "\\\"")
-                                    .replace('\n', "\\n")
-                                    .replace('\r', "\\r")
-                                    .replace('\t', "\\t")
-                            )
-                        } else {
-                            v.get(k).unwrap().to_string()
-                        }
-                    ))
+                    .map(|e| e.display_inner(config, true))
                     .collect::<Vec<String>>()
                     .join(", ")
             ),
+            Value::Object(v) => {
+                let mut keys: Vec<&Value> = v.keys().collect();
+                if config.sort_keys {
+                    keys.sort();
+                }
+
+                // Strings are always quoted within objects
+                let render = |e: &Value| {
+                    if e.is_string() {
+                        quote_string(&e.as_string())
+                    } else {
+                        e.display_inner(config, true)
+                    }
+                };
+                format!(
+                    "{{{}}}",
+                    keys.iter()
+                        .map(|k| format!("{}:{}", render(k), render(v.get(k).unwrap())))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                )
+            }
             Value::Identifier(s) => s.to_string(),
             Value::None => "".to_string(),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(n) => n.to_string(),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(b) => hex_string(b),
         }
     }
 
@@ -219,6 +498,10 @@ impl Value {
             Value::String(s) => !s.is_empty(),
             Value::Array(v) => v.iter().any(|e| e.as_bool()),
             Value::Object(v) => v.values().any(|e| e.as_bool()),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(n) => n.sign() != num_bigint::Sign::NoSign,
+            #[cfg(feature = "bytes")]
+            Value::Bytes(b) => !b.is_empty(),
         }
     }
 
@@ -233,6 +516,10 @@ impl Value {
             Value::String(_) => None,
             Value::Array(_) => None,
             Value::Object(_) => None,
+            #[cfg(feature = "bigint")]
+            Value::BigInt(n) => IntegerType::try_from(n).ok(),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(_) => None,
         }
     }
 
@@ -247,9 +534,31 @@ impl Value {
             Value::String(_) => None,
             Value::Array(_) => None,
             Value::Object(_) => None,
+            #[cfg(feature = "bigint")]
+            Value::BigInt(n) => n.to_string().parse().ok(),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(_) => None,
         }
     }
 
+    /// Return the value as an integer, or an error describing why it could not be converted
+    pub fn try_as_int(&self) -> Result<IntegerType, Error> {
+        self.as_int().ok_or_else(|| Error::ValueType {
+            value: self.clone(),
+            expected_type: ExpectedTypes::Int,
+            token: Token::dummy(&self.as_string()),
+        })
+    }
+
+    /// Return the value as a float, or an error describing why it could not be converted
+    pub fn try_as_float(&self) -> Result<FloatType, Error> {
+        self.as_float().ok_or_else(|| Error::ValueType {
+            value: self.clone(),
+            expected_type: ExpectedTypes::Float,
+            token: Token::dummy(&self.as_string()),
+        })
+    }
+
     /// Return the value as an array
     pub fn as_array(&self) -> ArrayType {
         match self {
@@ -261,6 +570,13 @@ impl Value {
             Value::String(_) => vec![self.clone()],
             Value::Array(v) => v.clone(),
             Value::Object(v) => v.values().cloned().collect(),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => vec![self.clone()],
+            #[cfg(feature = "bytes")]
+            Value::Bytes(b) => b
+                .iter()
+                .map(|e| Value::Integer(IntegerType::from(*e)))
+                .collect(),
         }
     }
 
@@ -277,6 +593,60 @@ impl Value {
         }
     }
 
+    /// Apply a transformation to each element of an array
+    /// Values that are not arrays are transformed directly
+    ///
+    /// # Arguments
+    /// * `f` - Transformation to apply
+    pub fn map_array<F, E>(&self, mut f: F) -> Result<Value, E>
+    where
+        F: FnMut(&Value) -> Result<Value, E>,
+    {
+        match self {
+            Value::Array(v) => Ok(Value::Array(
+                v.iter().map(&mut f).collect::<Result<ArrayType, E>>()?,
+            )),
+            _ => f(self),
+        }
+    }
+
+    /// Apply a transformation to each element of an array, or each value of an object
+    /// Values that are neither are transformed directly
+    ///
+    /// # Arguments
+    /// * `f` - Transformation to apply
+    pub fn map_values<F, E>(&self, mut f: F) -> Result<Value, E>
+    where
+        F: FnMut(&Value) -> Result<Value, E>,
+    {
+        match self {
+            Value::Object(v) => Ok(Value::Object(
+                v.iter()
+                    .map(|(k, v)| Ok((k.clone(), f(v)?)))
+                    .collect::<Result<ObjectType, E>>()?,
+            )),
+            _ => self.map_array(f),
+        }
+    }
+
+    /// Returns the name of the value's type
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::None => "none",
+            Value::Identifier(_) => "identifier",
+            Value::Boolean(_) => "boolean",
+            Value::Integer(_) => "integer",
+            Value::Float(_) => "float",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+            #[cfg(feature = "bigint")]
+            Value::BigInt(_) => "bigint",
+            #[cfg(feature = "bytes")]
+            Value::Bytes(_) => "bytes",
+        }
+    }
+
     /// Determine if the value is a boolean
     pub fn is_bool(&self) -> bool {
         matches!(self, Value::Boolean(_))
@@ -292,6 +662,46 @@ impl Value {
         matches!(self, Value::Float(_))
     }
 
+    /// Determine if the value is an arbitrary-precision integer
+    #[cfg(feature = "bigint")]
+    pub fn is_bigint(&self) -> bool {
+        matches!(self, Value::BigInt(_))
+    }
+
+    /// Return the value as an arbitrary-precision integer, if it is an integer
+    #[cfg(feature = "bigint")]
+    pub fn as_bigint(&self) -> Option<BigIntType> {
+        match self {
+            Value::Integer(n) => Some(BigIntType::from(*n)),
+            Value::BigInt(n) => Some(n.clone()),
+            _ => None,
+        }
+    }
+
+    /// Determine if the value is a sequence of raw bytes
+    #[cfg(feature = "bytes")]
+    pub fn is_bytes(&self) -> bool {
+        matches!(self, Value::Bytes(_))
+    }
+
+    /// Return the value as raw bytes, if possible
+    /// Strings become their UTF-8 encoding, and arrays must contain only integers from 0 to 255
+    #[cfg(feature = "bytes")]
+    pub fn as_bytes(&self) -> Option<BytesType> {
+        match self {
+            Value::Bytes(b) => Some(b.clone()),
+            Value::String(s) => Some(s.as_bytes().to_vec()),
+            Value::Array(v) => v
+                .iter()
+                .map(|e| match e {
+                    Value::Integer(n) => u8::try_from(*n).ok(),
+                    _ => None,
+                })
+                .collect(),
+            _ => None,
+        }
+    }
+
     /// Determine if the value is a float or int
     pub fn is_numeric(&self) -> bool {
         self.is_float() || self.is_int()
@@ -345,6 +755,57 @@ impl Value {
             None
         }
     }
+
+    /// Serialize the value to the versioned wire format, a JSON string of the form
+    /// `{"version": 1, "value": {"type": "integer", "value": 5}}`
+    pub fn to_wire(&self) -> String {
+        serde_json::to_string(&WireEnvelope {
+            version: WIRE_FORMAT_VERSION,
+            value: WireValue::from(self),
+        })
+        .unwrap_or_default()
+    }
+
+    /// Reconstruct a value from the wire format produced by [Value::to_wire]
+    /// Returns None if the input is malformed, or uses an unsupported version
+    ///
+    /// # Arguments
+    /// * `input` - Wire format JSON string
+    pub fn from_wire(input: &str) -> Option<Self> {
+        match serde_json::from_str::<WireEnvelope>(input) {
+            Ok(envelope) if envelope.version == WIRE_FORMAT_VERSION => {
+                Value::try_from(envelope.value).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert the value to plain JSON
+    /// Object keys are rendered as strings, and non-finite floats become null
+    pub fn to_json(&self) -> serde_json::Value {
+        match self {
+            Value::None => serde_json::Value::Null,
+            Value::Identifier(s) | Value::String(s) => serde_json::Value::String(s.to_string()),
+            Value::Boolean(b) => serde_json::Value::Bool(*b),
+            Value::Integer(n) => serde_json::Value::from(*n),
+            Value::Float(n) => serde_json::Number::from_f64(*n)
+                .map(serde_json::Value::Number)
+                .unwrap_or(serde_json::Value::Null),
+            Value::Array(v) => serde_json::Value::Array(v.iter().map(|e| e.to_json()).collect()),
+            Value::Object(v) => serde_json::Value::Object(
+                v.iter()
+                    .map(|(k, e)| (k.as_string(), e.to_json()))
+                    .collect(),
+            ),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(n) => match IntegerType::try_from(n) {
+                Ok(i) => serde_json::Value::from(i),
+                Err(_) => serde_json::Value::String(n.to_string()),
+            },
+            #[cfg(feature = "bytes")]
+            Value::Bytes(b) => serde_json::Value::String(hex_string(b)),
+        }
+    }
 }
 
 impl Clone for Value {
@@ -358,6 +819,10 @@ impl Clone for Value {
             Value::String(s) => Value::String(s.to_string()),
             Value::Array(v) => Value::Array(v.clone()),
             Value::Object(v) => Value::Object(v.clone()),
+            #[cfg(feature = "bigint")]
+            Value::BigInt(n) => Value::BigInt(n.clone()),
+            #[cfg(feature = "bytes")]
+            Value::Bytes(b) => Value::Bytes(b.clone()),
         }
     }
 }
@@ -376,20 +841,20 @@ impl PartialOrd for Value {
             (Value::Boolean(b1), _) => b1.partial_cmp(&other.as_bool()),
             (_, Value::Boolean(b2)) => self.as_bool().partial_cmp(b2),
 
-            // For objects, compare sorted values
+            // For objects, compare key/value pairs sorted by key
             (Value::Object(obj1), _) => {
-                let mut v1: Vec<_> = obj1.values().collect();
-                v1.sort();
                 let obj2 = other.as_object();
-                let mut v2: Vec<_> = obj2.values().collect();
+                let mut v1: Vec<_> = obj1.iter().collect();
+                v1.sort();
+                let mut v2: Vec<_> = obj2.iter().collect();
                 v2.sort();
                 v1.partial_cmp(&v2)
             }
             (_, Value::Object(obj2)) => {
                 let obj1 = self.as_object();
-                let mut v1: Vec<_> = obj1.values().collect();
+                let mut v1: Vec<_> = obj1.iter().collect();
                 v1.sort();
-                let mut v2: Vec<_> = obj2.values().collect();
+                let mut v2: Vec<_> = obj2.iter().collect();
                 v2.sort();
                 v1.partial_cmp(&v2)
             }
@@ -398,12 +863,32 @@ impl PartialOrd for Value {
             (Value::Array(a1), _) => a1.partial_cmp(&other.as_array()),
             (_, Value::Array(a2)) => self.as_array().partial_cmp(a2),
 
+            // Arbitrary-precision integers
+            #[cfg(feature = "bigint")]
+            (Value::BigInt(n1), Value::BigInt(n2)) => n1.partial_cmp(n2),
+            #[cfg(feature = "bigint")]
+            (Value::BigInt(n1), Value::Integer(i2)) => n1.partial_cmp(&BigIntType::from(*i2)),
+            #[cfg(feature = "bigint")]
+            (Value::Integer(i1), Value::BigInt(n2)) => BigIntType::from(*i1).partial_cmp(n2),
+            #[cfg(feature = "bigint")]
+            (Value::BigInt(_), Value::Float(f2)) => self.as_float()?.partial_cmp(f2),
+            #[cfg(feature = "bigint")]
+            (Value::Float(f1), Value::BigInt(_)) => f1.partial_cmp(&other.as_float()?),
+
             // Number to number
             (Value::Integer(i1), Value::Integer(i2)) => i1.partial_cmp(i2),
             (Value::Integer(i1), Value::Float(f2)) => (*i1 as f64).partial_cmp(f2),
             (Value::Float(f1), Value::Integer(i2)) => f1.partial_cmp(&(*i2 as f64)),
             (Value::Float(f1), Value::Float(f2)) => f1.partial_cmp(f2),
 
+            // Raw bytes compare bytewise, and as their hex string against anything else
+            #[cfg(feature = "bytes")]
+            (Value::Bytes(b1), Value::Bytes(b2)) => b1.partial_cmp(b2),
+            #[cfg(feature = "bytes")]
+            (Value::Bytes(_), _) | (_, Value::Bytes(_)) => {
+                self.as_string().partial_cmp(&other.as_string())
+            }
+
             // String comparisons, If one is a string, both are strings
             (Value::String(s1), _) => s1.partial_cmp(&other.as_string()),
             (_, Value::String(s2)) => self.as_string().partial_cmp(s2),
@@ -536,6 +1021,103 @@ mod test_atomic_value {
         assert_eq!("", Value::None.as_string());
     }
 
+    #[test]
+    fn test_display() {
+        let value = Value::Array(vec![
+            Value::Float(1.0 / 3.0),
+            Value::from("a"),
+            Value::Object(ObjectType::from([
+                (Value::from("b"), Value::Integer(2)),
+                (Value::from("a"), Value::from("x")),
+            ])),
+        ]);
+
+        let mut config = DisplayConfig {
+            sort_keys: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            "[0.33333333, a, {\"a\":\"x\", \"b\":2}]",
+            value.display(&config)
+        );
+
+        config.float_precision = 2;
+        config.quote_strings = true;
+        assert_eq!(
+            "[0.33, \"a\", {\"a\":\"x\", \"b\":2}]",
+            value.display(&config)
+        );
+
+        config.max_length = Some(8);
+        assert_eq!("[0.33, \"...", value.display(&config));
+
+        assert_eq!("0.33333333", Value::Float(1.0 / 3.0).as_string());
+    }
+
+    #[test]
+    fn test_display_exponential() {
+        assert_eq!("1e-10", Value::Float(1e-10).as_string());
+        assert_eq!("-2.5e-12", Value::Float(-2.5e-12).as_string());
+        assert_eq!("1.23456789e-9", Value::Float(1.2345678912e-9).as_string());
+        assert_eq!("0.0000005", Value::Float(5e-7).as_string());
+        assert_eq!("0.0", Value::Float(0.0).as_string());
+
+        assert_eq!("12345.5", Value::Float(12345.5).as_string());
+
+        // The threshold follows the configured precision
+        let config = DisplayConfig {
+            float_precision: 2,
+            ..Default::default()
+        };
+        assert_eq!("1e-3", Value::Float(0.001).display(&config));
+        assert_eq!("0.01", Value::Float(0.01).display(&config));
+
+        // Exponential output parses back to the same value
+        let mut state = crate::ParserState::new();
+        for n in [1e-10, -2.5e-12] {
+            let text = Value::Float(n).as_string();
+            assert_eq!(
+                Value::Float(n),
+                crate::Token::new(&text, &mut state).unwrap().value()
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_parseable_string() {
+        let mut state = crate::ParserState::new();
+        let values = [
+            Value::from("plain"),
+            Value::from("it's \"quoted\"\n\twith a \\ backslash"),
+            Value::Float(0.1 + 0.2),
+            Value::Float(1e-12),
+            Value::Integer(-5),
+            Value::Boolean(true),
+            Value::Array(vec![
+                Value::Integer(1),
+                Value::Array(vec![Value::from("a"), Value::Array(vec![])]),
+            ]),
+            Value::Object(ObjectType::from([
+                (Value::from("name"), Value::from("value")),
+                (
+                    Value::from("nested"),
+                    Value::Array(vec![Value::Float(2.5), Value::from("b")]),
+                ),
+                (Value::Integer(3), Value::Boolean(false)),
+            ])),
+        ];
+
+        for value in values {
+            let text = value.to_parseable_string();
+            assert_eq!(
+                value,
+                Token::new(&text, &mut state).unwrap().value(),
+                "{}",
+                text
+            );
+        }
+    }
+
     #[test]
     fn test_as_bool() {
         assert_eq!(true, Value::Float(5.0).as_bool());
@@ -565,6 +1147,130 @@ mod test_atomic_value {
         assert_eq!(false, Value::String("".to_string()).as_float().is_some());
     }
 
+    #[test]
+    fn test_wire_format() {
+        let values = vec![
+            Value::None,
+            Value::Identifier("x".to_string()),
+            Value::Boolean(true),
+            Value::Integer(-5),
+            Value::Float(2.5),
+            Value::from("te\"st"),
+            Value::Array(vec![
+                Value::Integer(1),
+                Value::Array(vec![Value::from("a")]),
+            ]),
+            Value::Object(ObjectType::from([
+                (Value::Integer(1), Value::from("one")),
+                (
+                    Value::from("nested"),
+                    Value::Object(ObjectType::from([(Value::Boolean(false), Value::None)])),
+                ),
+            ])),
+        ];
+        for value in values {
+            let wire = value.to_wire();
+            let result = Value::from_wire(&wire).unwrap();
+            assert_eq!(value.type_name(), result.type_name());
+            assert_eq!(value, result);
+        }
+
+        assert_eq!(
+            "{\"version\":1,\"value\":{\"type\":\"integer\",\"value\":5}}",
+            Value::Integer(5).to_wire()
+        );
+
+        // Non-finite floats are spelled out, since JSON has no representation for them
+        for n in [FloatType::INFINITY, FloatType::NEG_INFINITY] {
+            assert_eq!(
+                Value::Float(n),
+                Value::from_wire(&Value::Float(n).to_wire()).unwrap()
+            );
+        }
+        let wire = Value::Float(FloatType::NAN).to_wire();
+        assert_eq!(
+            "{\"version\":1,\"value\":{\"type\":\"float\",\"value\":\"NaN\"}}",
+            wire
+        );
+        assert_eq!(
+            true,
+            Value::from_wire(&wire)
+                .unwrap()
+                .as_float()
+                .unwrap()
+                .is_nan()
+        );
+        assert_eq!(
+            true,
+            Value::from_wire("{\"version\":1,\"value\":{\"type\":\"float\",\"value\":\"x\"}}")
+                .is_none()
+        );
+        assert_eq!(
+            true,
+            Value::from_wire("{\"version\":99,\"value\":{\"type\":\"none\"}}").is_none()
+        );
+
+        #[cfg(feature = "bigint")]
+        assert!(Value::from_wire(
+            "{\"version\":1,\"value\":{\"type\":\"array\",\"value\":[{\"type\":\"bigint\",\"value\":\"12x\"}]}}"
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(
+            serde_json::json!([1, 2.5, "a", null]),
+            Value::Array(vec![
+                Value::Integer(1),
+                Value::Float(2.5),
+                Value::from("a"),
+                Value::None
+            ])
+            .to_json()
+        );
+        assert_eq!(
+            serde_json::json!({"a": [true]}),
+            Value::Object(ObjectType::from([(
+                Value::from("a"),
+                Value::Array(vec![Value::Boolean(true)])
+            )]))
+            .to_json()
+        );
+    }
+
+    #[test]
+    fn test_try_as_int() {
+        assert_eq!(5, Value::Float(5.0).try_as_int().unwrap());
+        assert_eq!(5, Value::Integer(5).try_as_int().unwrap());
+        assert_eq!(
+            true,
+            matches!(
+                Value::String("test".to_string()).try_as_int(),
+                Err(Error::ValueType {
+                    expected_type: ExpectedTypes::Int,
+                    ..
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn test_try_as_float() {
+        assert_eq!(5.0, Value::Float(5.0).try_as_float().unwrap());
+        assert_eq!(5.0, Value::Integer(5).try_as_float().unwrap());
+        assert_eq!(
+            true,
+            matches!(
+                Value::String("test".to_string()).try_as_float(),
+                Err(Error::ValueType {
+                    expected_type: ExpectedTypes::Float,
+                    ..
+                })
+            )
+        );
+    }
+
     #[test]
     fn test_as_array() {
         assert_eq!(1, Value::Float(5.0).as_array().len());
@@ -576,6 +1282,74 @@ mod test_atomic_value {
         );
     }
 
+    #[test]
+    fn test_map_array() {
+        let double =
+            |v: &Value| -> Result<Value, ()> { Ok(Value::Integer(v.as_int().unwrap() * 2)) };
+        assert_eq!(
+            Value::Array(vec![Value::Integer(2), Value::Integer(4)]),
+            Value::Array(vec![Value::Integer(1), Value::Integer(2)])
+                .map_array(double)
+                .unwrap()
+        );
+        assert_eq!(
+            Value::Integer(6),
+            Value::Integer(3).map_array(double).unwrap()
+        );
+        assert_eq!(
+            Err(()),
+            Value::Array(vec![Value::Integer(1)]).map_array(|_| Err(()))
+        );
+    }
+
+    #[test]
+    fn test_map_values() {
+        let double =
+            |v: &Value| -> Result<Value, ()> { Ok(Value::Integer(v.as_int().unwrap() * 2)) };
+        assert_eq!(
+            Value::Object(ObjectType::from([
+                (Value::from("a"), Value::Integer(2)),
+                (Value::from("b"), Value::Integer(4)),
+            ])),
+            Value::Object(ObjectType::from([
+                (Value::from("a"), Value::Integer(1)),
+                (Value::from("b"), Value::Integer(2)),
+            ]))
+            .map_values(double)
+            .unwrap()
+        );
+        assert_eq!(
+            Value::Array(vec![Value::Integer(2)]),
+            Value::Array(vec![Value::Integer(1)])
+                .map_values(double)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_object_ordering() {
+        let a = Value::Object(ObjectType::from([(Value::Integer(1), Value::Integer(2))]));
+        let b = Value::Object(ObjectType::from([(Value::Integer(3), Value::Integer(2))]));
+        assert_eq!(false, a == b);
+        assert_eq!(true, a < b);
+
+        let mut c = ObjectType::new();
+        c.insert(Value::from("x"), Value::Integer(1));
+        c.insert(Value::from("y"), Value::Integer(2));
+        let mut d = ObjectType::new();
+        d.insert(Value::from("y"), Value::Integer(2));
+        d.insert(Value::from("x"), Value::Integer(1));
+        let (c, d) = (Value::Object(c), Value::Object(d));
+        assert_eq!(c, d);
+
+        let mut hasher = DefaultHasher::new();
+        c.hash(&mut hasher);
+        let hc = hasher.finish();
+        hasher = DefaultHasher::new();
+        d.hash(&mut hasher);
+        assert_eq!(hc, hasher.finish());
+    }
+
     #[test]
     fn test_hash() {
         let mut hasher = DefaultHasher::new();